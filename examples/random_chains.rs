@@ -9,13 +9,16 @@ use fnv::FnvHashMap;
 use gfx_chain::chain::Chain;
 use gfx_chain::collect::{collect, Chains};
 use gfx_chain::pass::{Pass, PassId, StateUsage};
-use gfx_chain::resource::{Buffer, BufferLayout, Id, Image, Layout, Resource, State, Usage};
+use gfx_chain::resource::{
+    Access, Buffer, BufferLayout, Id, Image, Layout, Resource, State, Usage,
+};
 use gfx_chain::schedule::{QueueId, SubmissionId};
-use gfx_chain::sync::{sync, Barrier, SyncData};
+use gfx_chain::stage::{all_commands, earliest, supported_by};
+use gfx_chain::sync::{sync_with_queue_types, Barrier, SyncData};
 use hal::buffer::Access as BufferAccess;
 use hal::image::{Access as ImageAccess, Layout as ImageLayout};
 use hal::pso::PipelineStage;
-use hal::queue::QueueFamilyId;
+use hal::queue::{QueueFamilyId, QueueType};
 use rand::{Isaac64Rng, OsRng, Rng, SeedableRng};
 use std::cmp::min;
 use std::collections::{HashMap, HashSet};
@@ -27,6 +30,9 @@ type DefaultRng = Isaac64Rng;
 fn gen_bool(rng: &mut DefaultRng) -> bool {
     rng.gen_range(0, 2) == 0
 }
+fn gen_one_in(rng: &mut DefaultRng, n: u32) -> bool {
+    rng.gen_range(0, n) == 0
+}
 fn gen_inclusive_u32(rng: &mut DefaultRng, low: u32, high: u32) -> u32 {
     rng.gen_range(low, high + 1)
 }
@@ -177,7 +183,29 @@ fn create_resc_deps<R: Resource, F: Fn(&mut DefaultRng) -> State<R>>(
     map
 }
 
+fn set_chain_flags<R: Resource>(rng: &mut DefaultRng, chains: &mut FnvHashMap<Id<R>, Chain<R>>) {
+    let mut ids: Vec<_> = chains.keys().cloned().collect();
+    ids.sort();
+    for id in ids {
+        let chain = chains.get_mut(&id).unwrap();
+        chain.set_conservative(gen_one_in(rng, 4));
+        chain.set_fresh(gen_one_in(rng, 4));
+        chain.set_concurrent(gen_one_in(rng, 4));
+        chain.set_external(gen_one_in(rng, 8));
+    }
+}
+
 static mut PANIC_INFO: Option<Option<String>> = None;
+fn queue_type(family: QueueFamilyId) -> QueueType {
+    const TYPES: &[QueueType] = &[
+        QueueType::General,
+        QueueType::Graphics,
+        QueueType::Compute,
+        QueueType::Transfer,
+    ];
+    TYPES[family.0 % TYPES.len()]
+}
+
 fn install_fuzz_panic_hook() {
     set_hook(Box::new(|panic_info| {
         if let Some(location) = panic_info.location() {
@@ -265,28 +293,16 @@ impl<'a, 'b> ExecuteStatus<'a, 'b> {
 
         let mut buffer_state = HashMap::new();
         for (&id, buffer) in &chains.buffers {
-            let link = buffer.link(0);
-            buffer_state.insert(
-                id,
-                ResourceState {
-                    access: link.state().access,
-                    layout: link.state().layout,
-                    owner: ResourceOwner::OnQueue(link.family()),
-                },
-            );
+            if let Some(state) = Self::initial_state(buffer) {
+                buffer_state.insert(id, state);
+            }
         }
 
         let mut image_state = HashMap::new();
         for (&id, image) in &chains.images {
-            let link = image.link(0);
-            image_state.insert(
-                id,
-                ResourceState {
-                    access: link.state().access,
-                    layout: link.state().layout,
-                    owner: ResourceOwner::OnQueue(link.family()),
-                },
-            );
+            if let Some(state) = Self::initial_state(image) {
+                image_state.insert(id, state);
+            }
         }
 
         let completed_passes = fill(passes.len());
@@ -304,10 +320,39 @@ impl<'a, 'b> ExecuteStatus<'a, 'b> {
         }
     }
 
+    fn initial_state<R: Resource>(chain: &Chain<R>) -> Option<ResourceState<R>> {
+        if chain.external() {
+            // Externally synchronized resources are not tracked.
+            return None;
+        }
+        let link = chain.link(0);
+        let owner = ResourceOwner::OnQueue(link.family());
+        if chain.fresh() && link.state().layout != R::Layout::discard_content() {
+            Some(ResourceState {
+                access: R::Access::none(),
+                layout: R::Layout::discard_content(),
+                owner,
+            })
+        } else {
+            Some(ResourceState {
+                access: link.state().access,
+                layout: link.state().layout,
+                owner,
+            })
+        }
+    }
+
+    fn ownership_only<R: Resource>(barrier: &Barrier<R>) -> bool {
+        barrier.queues.is_some()
+            && barrier.states.start.access == R::Access::none()
+            && barrier.states.end.access == R::Access::none()
+            && barrier.states.start.layout == barrier.states.end.layout
+    }
     fn barrier_new_state<R: Resource>(
         current_family: QueueFamilyId,
         barrier: &Barrier<R>,
         old_state: ResourceState<R>,
+        link_access: R::Access,
     ) -> ResourceState<R> {
         let mut new_state = old_state;
         if let Some(ref transfer) = barrier.queues {
@@ -345,22 +390,72 @@ impl<'a, 'b> ExecuteStatus<'a, 'b> {
 
         // TODO: Check that the transition done by the transfer out matches the transfer in
 
-        assert_eq!(
-            barrier.states.start.access, old_state.access,
-            "Resource source access flags do not match actual resource access."
-        );
-        new_state.access = barrier.states.end.access;
+        if Self::ownership_only(barrier) {
+            // Resource that is only read has nothing to make available or visible.
+            assert!(
+                !old_state.access.is_write() && !link_access.is_write(),
+                "Ownership-only transfer of written resource."
+            );
+            if barrier.queues.as_ref().unwrap().end.family() == current_family {
+                new_state.access = link_access;
+            }
+        } else {
+            assert_eq!(
+                barrier.states.start.access, old_state.access,
+                "Resource source access flags do not match actual resource access."
+            );
+            new_state.access = barrier.states.end.access;
+        }
 
         new_state
     }
     fn execute_barrier<R: Resource>(
         map: &mut HashMap<Id<R>, ResourceState<R>>,
+        chains: &FnvHashMap<Id<R>, Chain<R>>,
         current_family: QueueFamilyId,
         id: Id<R>,
         barrier: &Barrier<R>,
+        link_id: usize,
     ) {
+        let chain = chains.get(&id).expect("Resource not in chain!");
+        assert!(
+            !chain.external(),
+            "Barrier generated for externally synchronized resource."
+        );
+        if chain.concurrent() {
+            assert!(
+                barrier.queues.is_none(),
+                "Ownership transferred for concurrently shared resource."
+            );
+        }
+        if chain.conservative() && !barrier.states.end.stages.is_empty() {
+            let all = all_commands() & supported_by(queue_type(current_family));
+            assert_eq!(
+                barrier.states.end.stages,
+                if barrier.needs_memory_barrier() || barrier.queues.is_some() {
+                    all
+                } else {
+                    earliest(all)
+                },
+                "Barrier of conservative resource doesn't wait for all commands."
+            );
+        }
+        if barrier.states.start.access.is_write() {
+            assert!(
+                barrier.needs_memory_barrier(),
+                "Barrier after write doesn't require memory dependency."
+            );
+        }
+        if Self::ownership_only(barrier) {
+            assert!(
+                !barrier.needs_memory_barrier(),
+                "Ownership-only transfer requires memory dependency."
+            );
+        }
+
         let old_state = *map.get(&id).expect("Resource not in chain!");
-        let new_state = Self::barrier_new_state(current_family, barrier, old_state);
+        let link_access = chain.link(link_id).state().access;
+        let new_state = Self::barrier_new_state(current_family, barrier, old_state, link_access);
         map.insert(id, new_state);
     }
     fn can_execute_guard(&self, sid: SubmissionId, is_release: bool) -> bool {
@@ -404,10 +499,24 @@ impl<'a, 'b> ExecuteStatus<'a, 'b> {
         }
 
         for (&id, barrier) in &guard.buffers {
-            Self::execute_barrier(&mut self.buffer_state, sid.family(), id, barrier);
+            Self::execute_barrier(
+                &mut self.buffer_state,
+                &self.chains.buffers,
+                sid.family(),
+                id,
+                barrier,
+                sub.buffer(id),
+            );
         }
         for (&id, barrier) in &guard.images {
-            Self::execute_barrier(&mut self.image_state, sid.family(), id, barrier);
+            Self::execute_barrier(
+                &mut self.image_state,
+                &self.chains.images,
+                sid.family(),
+                id,
+                barrier,
+                sub.image(id),
+            );
         }
 
         for signal in &guard.signal {
@@ -425,17 +534,20 @@ impl<'a, 'b> ExecuteStatus<'a, 'b> {
         expected_state: StateUsage<R>,
         link_id: usize,
     ) {
+        let chain = chains.get(&id).expect("Resource not in chain!");
+        if chain.external() {
+            return;
+        }
         let state = *map.get(&id).expect("Resource not in chain!");
-        let link = chains
-            .get(&id)
-            .expect("Resource not in chain!")
-            .link(link_id);
+        let link = chain.link(link_id);
 
-        assert_eq!(
-            state.owner,
-            ResourceOwner::OnQueue(current_family),
-            "Resource is not currently owned by the queue executing this pass."
-        );
+        if !chain.concurrent() {
+            assert_eq!(
+                state.owner,
+                ResourceOwner::OnQueue(current_family),
+                "Resource is not currently owned by the queue executing this pass."
+            );
+        }
         assert_eq!(
             state.layout,
             link.state().layout,
@@ -694,13 +806,15 @@ fn test_run(seed: u64, is_test: bool, bench: Option<BenchParams>) -> (usize, Dur
 
     let now = Instant::now();
     catch_unwind(AssertUnwindSafe(|| {
-        let chains = collect(shuffled_passes, |QueueFamilyId(id)| max_queues[id]);
+        let mut chains = collect(shuffled_passes, |QueueFamilyId(id)| max_queues[id]);
+        set_chain_flags(rng, &mut chains.buffers);
+        set_chain_flags(rng, &mut chains.images);
         if is_test {
             println!("Unsynched chains: {:#?}", chains);
         }
 
         let mut semaphore_id = 0;
-        let schedule = sync(&chains, || {
+        let schedule = sync_with_queue_types(&chains, queue_type, || {
             let id = semaphore_id;
             semaphore_id += 1;
             (id, id)
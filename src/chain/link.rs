@@ -46,6 +46,7 @@ pub struct Link<R: Resource> {
     usage: R::Usage,
     state: State<R>,
     queue_count: usize,
+    submission_count: usize,
     queues: Vec<Option<LinkQueueState<R>>>,
     family: QueueFamilyId,
 }
@@ -65,6 +66,7 @@ where
         let mut link = Link {
             state,
             queue_count: 1,
+            submission_count: 1,
             queues: Vec::new(),
             family: sid.family(),
             usage,
@@ -100,6 +102,16 @@ where
         self.state
     }
 
+    /// Set layout of the link.
+    pub(crate) fn set_layout(&mut self, layout: R::Layout) {
+        self.state.layout = layout;
+    }
+
+    /// Get number of submissions associated with the link.
+    pub fn submission_count(&self) -> usize {
+        self.submission_count
+    }
+
     /// Check if the link is associated with only one queue.
     pub fn single_queue(&self) -> bool {
         self.queue_count == 1
@@ -139,6 +151,7 @@ where
         }
         self.state = state;
         self.usage |= usage;
+        self.submission_count += 1;
     }
 
     /// Check if ownership transfer is required between those links.
@@ -8,8 +8,18 @@
 mod link;
 
 use fnv::FnvHashMap;
-use resource::{Buffer, Id, Image, Resource, Usage};
-use std::ops::BitOr;
+use hal::image::Layout as ImageLayout;
+use hal::pso::PipelineStage;
+use hal::queue::{QueueFamilyId, QueueType};
+use resource::{Access, Buffer, Id, Image, Layout, Resource, State, Usage};
+use schedule::{QueueId, Schedule, SubmissionId};
+use stage::supported_by;
+use std::cmp::{max, min};
+use std::error::Error;
+use std::fmt;
+use std::iter::once;
+use std::ops::{BitOr, Range};
+use sync::{boundary_condition, BoundaryCondition};
 
 pub use self::link::Link;
 
@@ -18,6 +28,11 @@ pub use self::link::Link;
 #[derive(Clone, Debug)]
 pub struct Chain<R: Resource> {
     links: Vec<Link<R>>,
+    conservative: bool,
+    fresh: bool,
+    concurrent: bool,
+    external: bool,
+    name: Option<String>,
 }
 
 impl<R> Chain<R>
@@ -29,9 +44,81 @@ where
         &self.links
     }
 
+    /// Check if chain has no links.
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
     /// Create new empty `Chain`
     pub fn new() -> Self {
-        Chain { links: Vec::new() }
+        Chain {
+            links: Vec::new(),
+            conservative: false,
+            fresh: false,
+            concurrent: false,
+            external: false,
+            name: None,
+        }
+    }
+
+    /// Check if chain is conservative.
+    /// Conservative chains wait for all commands supported by queues
+    /// instead of stages declared by links.
+    pub fn conservative(&self) -> bool {
+        self.conservative
+    }
+
+    /// Mark chain as conservative (or not).
+    /// Useful when resources are accessed by externally recorded commands with unknown stages.
+    pub fn set_conservative(&mut self, conservative: bool) {
+        self.conservative = conservative;
+    }
+
+    /// Check if chain is fresh.
+    /// Resources of fresh chains have no content prior to the first link.
+    pub fn fresh(&self) -> bool {
+        self.fresh
+    }
+
+    /// Mark chain as fresh (or not).
+    /// Fresh chain transitions resource from discarded content before the first link.
+    /// Useful for transient resources allocated each frame.
+    pub fn set_fresh(&mut self, fresh: bool) {
+        self.fresh = fresh;
+    }
+
+    /// Check if resources of the chain are shared concurrently between queue families.
+    pub fn concurrent(&self) -> bool {
+        self.concurrent
+    }
+
+    /// Mark resources of the chain as shared concurrently between queue families (or not).
+    /// Concurrently shared resources require no ownership transfer between families.
+    pub fn set_concurrent(&mut self, concurrent: bool) {
+        self.concurrent = concurrent;
+    }
+
+    /// Check if resources of the chain are synchronized externally.
+    pub fn external(&self) -> bool {
+        self.external
+    }
+
+    /// Mark resources of the chain as synchronized externally (or not).
+    /// `sync` generates no barriers or semaphores for external chains.
+    /// Their links are still collected so usage and lifetime can be queried.
+    pub fn set_external(&mut self, external: bool) {
+        self.external = external;
+    }
+
+    /// Get name of the chain. Or `None` if chain is unnamed.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Set name of the chain.
+    /// Name is used in diagnostics instead of the resource id.
+    pub fn set_name<N: Into<String>>(&mut self, name: N) {
+        self.name = Some(name.into());
     }
 
     /// Get links slice
@@ -79,6 +166,244 @@ where
         self.link_mut(index)
     }
 
+    /// Override layout of the link.
+    /// Barriers generated by `sync` will transition resource into and out of the new layout.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if resource can't be accessed as declared by link in the new layout.
+    ///
+    pub fn override_layout(&mut self, index: usize, layout: R::Layout) {
+        let name = self.name.as_deref();
+        let link = &mut self.links[index];
+        assert!(
+            R::layout_supports(layout, link.state().access),
+            "Layout {:?} doesn't support access {:?} of link {}{}",
+            layout,
+            link.state().access,
+            index,
+            OfChain(name)
+        );
+        link.set_layout(layout);
+    }
+
+    /// Get layouts of the resource before the first link and after the last link.
+    /// Fresh chains start from the layout in which content is discarded.
+    /// Returns `None` if chain has no links.
+    pub fn layouts(&self) -> Option<Range<R::Layout>> {
+        let first = self.links.first()?;
+        let last = self.links.last()?;
+        let initial = if self.fresh {
+            R::Layout::discard_content()
+        } else {
+            first.state().layout
+        };
+        Some(initial..last.state().layout)
+    }
+
+    /// Get distinct queues on which links of the chain are executed.
+    /// Queues are listed in order of the first link executed on them.
+    pub fn queues(&self) -> Vec<QueueId> {
+        let mut queues = Vec::new();
+        for (qid, _) in self.links.iter().flat_map(Link::queues) {
+            if !queues.contains(&qid) {
+                queues.push(qid);
+            }
+        }
+        queues
+    }
+
+    /// Get distinct queue families that own resource at links of the chain.
+    /// Families are listed in order of the first link owned by them.
+    pub fn queue_families(&self) -> Vec<QueueFamilyId> {
+        let mut families = Vec::new();
+        for family in self.links.iter().map(Link::family) {
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        }
+        families
+    }
+
+    /// Get lifetime of the resource as range of submit orders.
+    /// Range starts at the first submission that accesses the resource and
+    /// ends after the last one.
+    /// Resources of chains with non-overlapping lifetimes can share memory.
+    /// Returns `None` if chain has no links.
+    pub fn lifetime<S>(&self, schedule: &Schedule<S>) -> Option<Range<usize>> {
+        let mut orders = self
+            .links
+            .iter()
+            .flat_map(Link::queues)
+            .flat_map(|(qid, queue)| {
+                let first = schedule[SubmissionId::new(qid, queue.first)].submit_order();
+                let last = schedule[SubmissionId::new(qid, queue.last)].submit_order();
+                once(first).chain(once(last))
+            });
+        let first = orders.next()?;
+        Some(orders.fold(first..first + 1, |range, order| {
+            min(range.start, order)..max(range.end, order + 1)
+        }))
+    }
+
+    /// Get queue family that owns resource at all links of the chain.
+    /// Returns error listing links owned by other families if there are any.
+    /// Returns `None` in `Ok` if chain has no links.
+    pub fn assert_single_family(&self) -> Result<Option<QueueFamilyId>, MultiFamilyError> {
+        let family = match self.links.first() {
+            Some(link) => link.family(),
+            None => return Ok(None),
+        };
+        let links: Vec<_> = self
+            .links
+            .iter()
+            .enumerate()
+            .filter(|&(_, link)| link.family() != family)
+            .map(|(index, _)| index)
+            .collect();
+        if links.is_empty() {
+            Ok(Some(family))
+        } else {
+            Err(MultiFamilyError {
+                family,
+                links,
+                name: self.name.clone(),
+            })
+        }
+    }
+
+    /// Get index of the last link with exclusive access.
+    /// Returns `None` if resource is never written.
+    pub fn last_writer(&self) -> Option<usize> {
+        self.links.iter().rposition(|link| link.state().exclusive())
+    }
+
+    /// Check if the chain is fresh and the first link writes resource without reading it.
+    /// Content prior to the first link doesn't matter then and needn't be uploaded.
+    /// Writes that cover only part of the resource can't be told apart here.
+    /// Returns `false` if chain is not fresh or has no links.
+    pub fn first_use_clears(&self) -> bool {
+        self.fresh()
+            && self.links.first().is_some_and(|link| {
+                let access = link.state().access;
+                access.is_write() && !access.is_read()
+            })
+    }
+
+    /// Check that resource can be bound to descriptor requiring `expected` usage at the link.
+    /// Link must declare that usage or access from which that usage is derived,
+    /// and link's access must include access types the descriptor is accessed through.
+    pub fn validate_descriptor(
+        &self,
+        index: usize,
+        expected: R::Usage,
+    ) -> Result<(), DescriptorMismatch<R>> {
+        let link = &self.links[index];
+        let access = link.state().access;
+        let found = link.usage() | R::derive_usage(access);
+        let required = R::usage_access(expected);
+        if found | expected == found && access & required != R::Access::none() {
+            Ok(())
+        } else {
+            Err(DescriptorMismatch {
+                link: index,
+                expected,
+                found,
+                required,
+                access,
+                name: self.name.clone(),
+            })
+        }
+    }
+
+    /// Get differences between links of this chain and the other.
+    /// Links and boundaries between them are compared by index.
+    pub fn diff(&self, other: &Self) -> Vec<LinkDiff<R>> {
+        let mut diffs = Vec::new();
+        for (index, (old, new)) in self.links.iter().zip(&other.links).enumerate() {
+            let (old_state, new_state) = (old.state(), new.state());
+            if old_state.access != new_state.access
+                || old_state.layout != new_state.layout
+                || old_state.stages != new_state.stages
+            {
+                diffs.push(LinkDiff::State(index, old_state..new_state));
+            }
+            let old_queues: Vec<_> = old.queues().map(|(qid, _)| qid).collect();
+            let new_queues: Vec<_> = new.queues().map(|(qid, _)| qid).collect();
+            if old_queues != new_queues {
+                diffs.push(LinkDiff::Queues(index, old_queues..new_queues));
+            }
+        }
+        let boundaries = |chain: &Self| -> Vec<_> {
+            chain
+                .links
+                .windows(2)
+                .map(|pair| boundary_condition(chain, &pair[0], &pair[1]))
+                .collect()
+        };
+        for (index, (old, new)) in boundaries(self)
+            .into_iter()
+            .zip(boundaries(other))
+            .enumerate()
+        {
+            if old != new {
+                diffs.push(LinkDiff::Boundary(index..index + 1, old..new));
+            }
+        }
+        diffs.extend((other.links.len()..self.links.len()).map(LinkDiff::Removed));
+        diffs.extend((self.links.len()..other.links.len()).map(LinkDiff::Added));
+        diffs
+    }
+
+    /// Check that stages of all links are supported by queues of families that own resource.
+    /// `queue_type` returns type of queues of the family.
+    pub fn validate_stages<Q>(&self, queue_type: Q) -> Result<(), UnsupportedStages>
+    where
+        Q: Fn(QueueFamilyId) -> QueueType,
+    {
+        for (index, link) in self.links.iter().enumerate() {
+            let stages = link.state().stages - supported_by(queue_type(link.family()));
+            if !stages.is_empty() {
+                return Err(UnsupportedStages {
+                    link: index,
+                    family: link.family(),
+                    stages,
+                    name: self.name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Get statistics of submissions merged into links.
+    pub fn merge_stats(&self) -> MergeStats {
+        self.links
+            .iter()
+            .fold(MergeStats::default(), |mut stats, link| {
+                if link.submission_count() > 1 {
+                    stats.merged += 1;
+                } else {
+                    stats.standalone += 1;
+                }
+                stats.eliminated += link.submission_count() - 1;
+                stats
+            })
+    }
+
+    /// Get submit order from which resource requires backing memory.
+    /// Fresh chains require memory from their first submission.
+    /// Content of other chains is carried from previous execution so memory is required
+    /// from the start of the schedule.
+    /// Returns `None` if chain has no links.
+    pub fn allocation_point<S>(&self, schedule: &Schedule<S>) -> Option<usize> {
+        let lifetime = self.lifetime(schedule)?;
+        if self.fresh {
+            Some(lifetime.start)
+        } else {
+            Some(0)
+        }
+    }
+
     /// Get total usage.
     pub fn usage(&self) -> R::Usage {
         self.links
@@ -86,8 +411,243 @@ where
             .map(Link::usage)
             .fold(R::Usage::none(), BitOr::bitor)
     }
+
+    /// Get total usage together with usage flags derived from accesses of the links.
+    /// Resource must be created with this usage to be accessed as declared by links.
+    pub fn required_usage(&self) -> R::Usage {
+        self.links
+            .iter()
+            .map(|link| link.usage() | R::derive_usage(link.state().access))
+            .fold(R::Usage::none(), BitOr::bitor)
+    }
+}
+
+impl Chain<Image> {
+    /// Get indices of links that can't be in the same render pass with previous link.
+    /// Layout transition from previous link to such link can't be performed by subpass dependency
+    /// as new layout can't be used by attachments.
+    pub fn render_pass_breaks(&self) -> Vec<usize> {
+        self.links
+            .windows(2)
+            .enumerate()
+            .filter(|&(_, pair)| {
+                let prev = pair[0].state().layout;
+                let next = pair[1].state().layout;
+                prev != next && !attachment_layout(next)
+            })
+            .map(|(index, _)| index + 1)
+            .collect()
+    }
 }
 
+/// Check if resources of two chains can't share memory.
+/// That is their memory ranges overlap and their lifetimes overlap.
+/// Chains without links never conflict.
+pub fn aliases_conflict<A, B, S>(
+    schedule: &Schedule<S>,
+    a: &Chain<A>,
+    memory_a: Range<u64>,
+    b: &Chain<B>,
+    memory_b: Range<u64>,
+) -> bool
+where
+    A: Resource,
+    B: Resource,
+{
+    let memory = memory_a.start < memory_b.end && memory_b.start < memory_a.end;
+    match (a.lifetime(schedule), b.lifetime(schedule)) {
+        (Some(a), Some(b)) => memory && a.start < b.end && b.start < a.end,
+        _ => false,
+    }
+}
+
+/// Check if layout can be used by attachments within render pass.
+fn attachment_layout(layout: ImageLayout) -> bool {
+    matches!(
+        layout,
+        ImageLayout::General
+            | ImageLayout::ColorAttachmentOptimal
+            | ImageLayout::DepthStencilAttachmentOptimal
+            | ImageLayout::DepthStencilReadOnlyOptimal
+            | ImageLayout::ShaderReadOnlyOptimal
+    )
+}
+
+/// Difference between links of two chains.
+#[derive(Clone, Debug)]
+pub enum LinkDiff<R: Resource> {
+    /// Link is present only in the other chain.
+    Added(usize),
+
+    /// Link is present only in this chain.
+    Removed(usize),
+
+    /// Links have different states.
+    State(usize, Range<State<R>>),
+
+    /// Links are executed on different queues.
+    Queues(usize, Range<Vec<QueueId>>),
+
+    /// Different conditions match for the boundary between the previous and the next link.
+    /// Synchronization generated for the boundary changes accordingly.
+    Boundary(Range<usize>, Range<BoundaryCondition>),
+}
+
+impl<R> fmt::Display for LinkDiff<R>
+where
+    R: Resource,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LinkDiff::Added(index) => write!(fmt, "link {}: added", index),
+            LinkDiff::Removed(index) => write!(fmt, "link {}: removed", index),
+            LinkDiff::State(index, ref states) => write!(
+                fmt,
+                "link {}: state {:?} -> {:?}",
+                index, states.start, states.end
+            ),
+            LinkDiff::Queues(index, ref queues) => write!(
+                fmt,
+                "link {}: queues {:?} -> {:?}",
+                index, queues.start, queues.end
+            ),
+            LinkDiff::Boundary(ref links, ref conditions) => write!(
+                fmt,
+                "links {}..{}: boundary {:?} -> {:?}",
+                links.start, links.end, conditions.start, conditions.end
+            ),
+        }
+    }
+}
+
+/// Statistics of submissions merged into links of the chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MergeStats {
+    /// Number of links with multiple submissions.
+    pub merged: usize,
+
+    /// Number of links with single submission.
+    pub standalone: usize,
+
+    /// Number of boundaries between submissions eliminated by merging.
+    /// Each eliminated boundary would require barrier or semaphore if every submission had its own link.
+    pub eliminated: usize,
+}
+
+/// Name of the chain to mention in diagnostics after the link.
+pub(crate) struct OfChain<'a>(pub(crate) Option<&'a str>);
+
+impl<'a> fmt::Display for OfChain<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(name) => write!(fmt, " of chain \"{}\"", name),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Error returned by `Chain::validate_stages`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnsupportedStages {
+    /// Index of the link.
+    pub link: usize,
+
+    /// Family that owns resource at the link.
+    pub family: QueueFamilyId,
+
+    /// Stages of the link unsupported by the family.
+    pub stages: PipelineStage,
+
+    /// Name of the chain.
+    pub name: Option<String>,
+}
+
+impl fmt::Display for UnsupportedStages {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Link {}{} uses stages {:?} unsupported by family {:?}",
+            self.link,
+            OfChain(self.name.as_deref()),
+            self.stages,
+            self.family
+        )
+    }
+}
+
+impl Error for UnsupportedStages {}
+
+/// Error returned by `Chain::validate_descriptor`.
+#[derive(Clone, Debug)]
+pub struct DescriptorMismatch<R: Resource> {
+    /// Index of the link.
+    pub link: usize,
+
+    /// Usage required by descriptor.
+    pub expected: R::Usage,
+
+    /// Usage declared by link or derived from its access.
+    pub found: R::Usage,
+
+    /// Access types through which descriptor accesses resource.
+    pub required: R::Access,
+
+    /// Access of the link.
+    pub access: R::Access,
+
+    /// Name of the chain.
+    pub name: Option<String>,
+}
+
+impl<R> fmt::Display for DescriptorMismatch<R>
+where
+    R: Resource,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Descriptor requires usage {:?} and access {:?} but link {}{} declares usage {:?} and access {:?}",
+            self.expected,
+            self.required,
+            self.link,
+            OfChain(self.name.as_deref()),
+            self.found,
+            self.access
+        )
+    }
+}
+
+impl<R> Error for DescriptorMismatch<R> where R: Resource {}
+
+/// Error returned by `Chain::assert_single_family`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiFamilyError {
+    /// Family of the first link.
+    pub family: QueueFamilyId,
+
+    /// Indices of links owned by other families.
+    pub links: Vec<usize>,
+
+    /// Name of the chain.
+    pub name: Option<String>,
+}
+
+impl fmt::Display for MultiFamilyError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(ref name) => write!(fmt, "Chain \"{}\"", name)?,
+            None => write!(fmt, "Chain")?,
+        }
+        write!(
+            fmt,
+            " starts on {:?} but links {:?} are owned by other families",
+            self.family, self.links
+        )
+    }
+}
+
+impl Error for MultiFamilyError {}
+
 /// Type alias for map of chains by id for buffers.
 pub type BufferChains = FnvHashMap<Id<Buffer>, Chain<Buffer>>;
 
@@ -35,6 +35,29 @@ pub struct Chains<S = Unsynchronized> {
     pub images: ImageChains,
 }
 
+impl<S> Chains<S> {
+    /// Get chain of the buffer.
+    /// Inserts empty chain if buffer isn't used by any pass.
+    /// Empty chains are valid and produce no synchronization.
+    pub fn reserve_buffer(&mut self, id: Id<Buffer>) -> &mut Chain<Buffer> {
+        self.buffers.entry(id).or_insert_with(Chain::new)
+    }
+
+    /// Get chain of the image.
+    /// Inserts empty chain if image isn't used by any pass.
+    /// Empty chains are valid and produce no synchronization.
+    pub fn reserve_image(&mut self, id: Id<Image>) -> &mut Chain<Image> {
+        self.images.entry(id).or_insert_with(Chain::new)
+    }
+
+    /// Remove chains without links.
+    /// Chains are keyed by resource ids so ids of remaining chains are unchanged.
+    pub fn compact(&mut self) {
+        self.buffers.retain(|_, chain| !chain.is_empty());
+        self.images.retain(|_, chain| !chain.is_empty());
+    }
+}
+
 #[derive(PartialEq, PartialOrd, Eq, Ord)]
 struct Fitness {
     transfers: usize,
@@ -12,13 +12,14 @@
 extern crate fnv;
 extern crate gfx_hal as hal;
 
-use hal::queue::QueueFamilyId;
+use hal::queue::{QueueFamilyId, QueueType};
 
 pub mod chain;
 pub mod collect;
 pub mod pass;
 pub mod resource;
 pub mod schedule;
+pub mod stage;
 pub mod sync;
 
 /// Allows to insert links to submission generically.
@@ -30,9 +31,13 @@ trait Pick<R> {
 
 use collect::{collect, Chains};
 use pass::Pass;
-use sync::{sync, SyncData};
+use sync::{
+    sync_explained_with_queue_types, sync_with_queue_types, sync_with_stats_with_queue_types,
+    BuildStats, Explanation, SyncData,
+};
 
 /// Build synchronized schedule of the execution from passes descriptions.
+/// Conservative chains wait for all commands.
 ///
 /// # Parameters
 ///
@@ -48,12 +53,127 @@ pub fn build<F, Q, S, W>(
 where
     Q: Fn(QueueFamilyId) -> usize,
     F: FnMut() -> (S, W),
+{
+    build_with_queue_types(passes, max_queues, |_| QueueType::General, new_semaphore)
+}
+
+/// Build synchronized schedule of the execution from passes descriptions.
+/// Stages used for conservative chains are limited to ones supported by queues of the family.
+///
+/// # Parameters
+///
+/// `passes`        - array of pass descriptions for passes to schedule and synchronize.
+/// `max_queues`    - function that returns maximum number of queues for specified family.
+/// `queue_type`    - function that returns type of queues of specified family.
+/// `new_semaphore` - function to create new semaphore pair - (signal, wait).
+///
+pub fn build_with_queue_types<F, Q, T, S, W>(
+    passes: Vec<Pass>,
+    max_queues: Q,
+    queue_type: T,
+    new_semaphore: F,
+) -> Chains<SyncData<S, W>>
+where
+    Q: Fn(QueueFamilyId) -> usize,
+    T: Fn(QueueFamilyId) -> QueueType,
+    F: FnMut() -> (S, W),
 {
     let chains = collect(passes, max_queues);
-    let schedule = sync(&chains, new_semaphore);
+    let schedule = sync_with_queue_types(&chains, queue_type, new_semaphore);
     Chains {
         schedule,
         images: chains.images,
         buffers: chains.buffers,
     }
 }
+
+/// Build synchronized schedule of the execution from passes descriptions.
+/// Additionally returns decisions made for each boundary between links.
+///
+/// See `build` for parameters description.
+///
+pub fn build_explained<F, Q, S, W>(
+    passes: Vec<Pass>,
+    max_queues: Q,
+    new_semaphore: F,
+) -> (Chains<SyncData<S, W>>, Explanation)
+where
+    Q: Fn(QueueFamilyId) -> usize,
+    F: FnMut() -> (S, W),
+{
+    build_explained_with_queue_types(passes, max_queues, |_| QueueType::General, new_semaphore)
+}
+
+/// Build synchronized schedule of the execution from passes descriptions.
+/// Additionally returns decisions made for each boundary between links.
+///
+/// See `build_with_queue_types` for parameters description.
+///
+pub fn build_explained_with_queue_types<F, Q, T, S, W>(
+    passes: Vec<Pass>,
+    max_queues: Q,
+    queue_type: T,
+    new_semaphore: F,
+) -> (Chains<SyncData<S, W>>, Explanation)
+where
+    Q: Fn(QueueFamilyId) -> usize,
+    T: Fn(QueueFamilyId) -> QueueType,
+    F: FnMut() -> (S, W),
+{
+    let chains = collect(passes, max_queues);
+    let (schedule, explanation) =
+        sync_explained_with_queue_types(&chains, queue_type, new_semaphore);
+    (
+        Chains {
+            schedule,
+            images: chains.images,
+            buffers: chains.buffers,
+        },
+        explanation,
+    )
+}
+
+/// Build synchronized schedule of the execution from passes descriptions.
+/// Additionally returns statistics of synchronization generated for each chain.
+///
+/// See `build` for parameters description.
+///
+pub fn build_with_stats<F, Q, S, W>(
+    passes: Vec<Pass>,
+    max_queues: Q,
+    new_semaphore: F,
+) -> (Chains<SyncData<S, W>>, BuildStats)
+where
+    Q: Fn(QueueFamilyId) -> usize,
+    F: FnMut() -> (S, W),
+{
+    build_with_stats_with_queue_types(passes, max_queues, |_| QueueType::General, new_semaphore)
+}
+
+/// Build synchronized schedule of the execution from passes descriptions.
+/// Additionally returns statistics of synchronization generated for each chain.
+///
+/// See `build_with_queue_types` for parameters description.
+///
+pub fn build_with_stats_with_queue_types<F, Q, T, S, W>(
+    passes: Vec<Pass>,
+    max_queues: Q,
+    queue_type: T,
+    new_semaphore: F,
+) -> (Chains<SyncData<S, W>>, BuildStats)
+where
+    Q: Fn(QueueFamilyId) -> usize,
+    T: Fn(QueueFamilyId) -> QueueType,
+    F: FnMut() -> (S, W),
+{
+    let chains = collect(passes, max_queues);
+    let (schedule, stats) = sync_with_stats_with_queue_types(&chains, queue_type, new_semaphore);
+    (
+        Chains {
+            schedule,
+            images: chains.images,
+            buffers: chains.buffers,
+        },
+        stats,
+    )
+}
@@ -3,8 +3,11 @@
 //! synchronize execution of passes.
 //!
 
+use hal::buffer::{Access as BufferAccess, Usage as BufferUsage};
+use hal::image::{Access as ImageAccess, Layout as ImageLayout, Usage as ImageUsage};
+use hal::pso::PipelineStage;
 use hal::queue::QueueFamilyId;
-use resource::{Buffer, Id, Image, Resource, State};
+use resource::{Access, Buffer, BufferLayout, Id, Image, Resource, State, Usage};
 use std::collections::hash_map::{HashMap, Iter as HashMapIter};
 
 /// Id of the pass.
@@ -21,6 +24,58 @@ pub struct StateUsage<R: Resource> {
     pub usage: R::Usage,
 }
 
+impl StateUsage<Buffer> {
+    /// State and usage of buffer read by transfer commands.
+    pub fn transfer_src() -> Self {
+        StateUsage {
+            state: State {
+                access: BufferAccess::TRANSFER_READ,
+                layout: BufferLayout,
+                stages: PipelineStage::TRANSFER,
+            },
+            usage: BufferUsage::TRANSFER_SRC,
+        }
+    }
+}
+
+impl StateUsage<Image> {
+    /// State and usage of image cleared outside of render pass.
+    /// Clear commands write to image in `TransferDstOptimal` layout at transfer stage.
+    pub fn clear() -> Self {
+        Self::transfer_dst()
+    }
+
+    /// State and usage of image written by transfer commands.
+    pub fn transfer_dst() -> Self {
+        StateUsage {
+            state: State {
+                access: ImageAccess::TRANSFER_WRITE,
+                layout: ImageLayout::TransferDstOptimal,
+                stages: PipelineStage::TRANSFER,
+            },
+            usage: ImageUsage::TRANSFER_DST,
+        }
+    }
+}
+
+impl<R> StateUsage<R>
+where
+    R: Resource,
+{
+    /// State and usage for pass that only transitions resource to the layout.
+    /// Barrier to the layout is generated but resource isn't accessed.
+    pub fn transition(layout: R::Layout) -> Self {
+        StateUsage {
+            state: State {
+                access: R::Access::none(),
+                layout,
+                stages: PipelineStage::BOTTOM_OF_PIPE,
+            },
+            usage: R::Usage::none(),
+        }
+    }
+}
+
 /// Description of pass.
 #[derive(Clone, Debug)]
 pub struct Pass {
@@ -45,6 +100,28 @@ pub struct Pass {
 }
 
 impl Pass {
+    /// Create pass that copies content of the buffer to the image.
+    /// Buffer is read and image is written by transfer commands.
+    pub fn copy_buffer_to_image(
+        id: PassId,
+        family: QueueFamilyId,
+        buffer: Id<Buffer>,
+        image: Id<Image>,
+    ) -> Self {
+        Pass {
+            id,
+            family,
+            queue: None,
+            dependencies: Vec::new(),
+            buffers: Some((buffer, StateUsage::transfer_src()))
+                .into_iter()
+                .collect(),
+            images: Some((image, StateUsage::transfer_dst()))
+                .into_iter()
+                .collect(),
+        }
+    }
+
     /// Get family on which this pass will be executed.
     pub fn family(&self) -> QueueFamilyId {
         self.family
@@ -69,4 +146,28 @@ impl Pass {
     pub fn images(&self) -> HashMapIter<Id<Image>, StateUsage<Image>> {
         self.images.iter()
     }
+
+    /// Get ids of buffers which states declare access types unsupported by declared stages.
+    pub fn inconsistent_buffers<'a>(&'a self) -> impl Iterator<Item = Id<Buffer>> + 'a {
+        self.buffers
+            .iter()
+            .filter(|&(_, state)| !state.state.unsupported_access().is_empty())
+            .map(|(&id, _)| id)
+    }
+
+    /// Get ids of images which states declare access types unsupported by declared stages.
+    pub fn inconsistent_images<'a>(&'a self) -> impl Iterator<Item = Id<Image>> + 'a {
+        self.images
+            .iter()
+            .filter(|&(_, state)| !state.state.unsupported_access().is_empty())
+            .map(|(&id, _)| id)
+    }
+
+    /// Get ids of images which states have layouts not optimal for access,
+    /// with suggested layouts.
+    pub fn suboptimal_images<'a>(&'a self) -> impl Iterator<Item = (Id<Image>, ImageLayout)> + 'a {
+        self.images
+            .iter()
+            .filter_map(|(&id, state)| state.state.suggest_layout().map(|layout| (id, layout)))
+    }
 }
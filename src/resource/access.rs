@@ -18,8 +18,15 @@ pub trait Access:
     /// Check if the access combination contains at least one write access type.
     fn is_write(&self) -> bool;
 
+    /// Check if the access combination contains at least one generic memory access type.
+    /// Default implementation reports no generic access types.
+    fn is_generic(&self) -> bool {
+        false
+    }
+
     /// Get set of supported stages.
     /// This function is valid only for single access type.
+    /// Generic memory access types are conservatively supported by all commands.
     ///
     /// # Panics
     ///
@@ -2,6 +2,7 @@ use hal::buffer::{Access as BufferAccess, Usage as BufferUsage};
 use hal::pso::PipelineStage;
 
 use resource::{Access, Layout, Usage};
+use stage::all_commands;
 
 impl Access for BufferAccess {
     fn none() -> Self {
@@ -19,6 +20,10 @@ impl Access for BufferAccess {
             || self.contains(Self::MEMORY_WRITE)
     }
 
+    fn is_generic(&self) -> bool {
+        self.intersects(Self::MEMORY_READ | Self::MEMORY_WRITE)
+    }
+
     fn is_read(&self) -> bool {
         self.contains(Self::TRANSFER_READ)
             || self.contains(Self::SHADER_READ)
@@ -41,7 +46,7 @@ impl Access for BufferAccess {
                 PS::VERTEX_SHADER | PS::GEOMETRY_SHADER | PS::FRAGMENT_SHADER | PS::COMPUTE_SHADER
             }
             Self::HOST_READ | Self::HOST_WRITE => PS::HOST,
-            Self::MEMORY_READ | Self::MEMORY_WRITE => PS::empty(),
+            Self::MEMORY_READ | Self::MEMORY_WRITE => all_commands(),
             _ => panic!("Only one bit must be set"),
         }
     }
@@ -69,3 +74,53 @@ impl Usage for BufferUsage {
         BufferUsage::all()
     }
 }
+
+/// Derive buffer usage flags required for access.
+pub(crate) fn derive_usage(access: BufferAccess) -> BufferUsage {
+    const TABLE: &[(BufferAccess, BufferUsage)] = &[
+        (BufferAccess::TRANSFER_READ, BufferUsage::TRANSFER_SRC),
+        (BufferAccess::TRANSFER_WRITE, BufferUsage::TRANSFER_DST),
+        (BufferAccess::INDEX_BUFFER_READ, BufferUsage::INDEX),
+        (BufferAccess::VERTEX_BUFFER_READ, BufferUsage::VERTEX),
+        (BufferAccess::INDIRECT_COMMAND_READ, BufferUsage::INDIRECT),
+        (BufferAccess::CONSTANT_BUFFER_READ, BufferUsage::UNIFORM),
+        // Shader accesses can be either to storage or storage texel buffer,
+        // so they derive nothing.
+    ];
+
+    TABLE
+        .iter()
+        .filter(|&&(flag, _)| access.contains(flag))
+        .fold(BufferUsage::empty(), |acc, &(_, usage)| acc | usage)
+}
+
+/// Get access types through which buffer bound with the usage can be accessed.
+pub(crate) fn usage_access(usage: BufferUsage) -> BufferAccess {
+    const TABLE: &[(BufferUsage, BufferAccess)] = &[
+        (BufferUsage::TRANSFER_SRC, BufferAccess::TRANSFER_READ),
+        (BufferUsage::TRANSFER_DST, BufferAccess::TRANSFER_WRITE),
+        (BufferUsage::UNIFORM_TEXEL, BufferAccess::SHADER_READ),
+        (BufferUsage::STORAGE_TEXEL, BufferAccess::SHADER_READ),
+        (BufferUsage::STORAGE_TEXEL, BufferAccess::SHADER_WRITE),
+        (BufferUsage::UNIFORM, BufferAccess::CONSTANT_BUFFER_READ),
+        (BufferUsage::STORAGE, BufferAccess::SHADER_READ),
+        (BufferUsage::STORAGE, BufferAccess::SHADER_WRITE),
+        (BufferUsage::INDEX, BufferAccess::INDEX_BUFFER_READ),
+        (BufferUsage::VERTEX, BufferAccess::VERTEX_BUFFER_READ),
+        (BufferUsage::INDIRECT, BufferAccess::INDIRECT_COMMAND_READ),
+    ];
+
+    TABLE
+        .iter()
+        .filter(|&&(flag, _)| usage.contains(flag))
+        .fold(BufferAccess::empty(), |acc, &(_, access)| acc | access)
+}
+
+/// Get access types not supported by any of the stages.
+pub(crate) fn unsupported_access(access: BufferAccess, stages: PipelineStage) -> BufferAccess {
+    (0..32)
+        .filter_map(|bit| BufferAccess::from_bits(1 << bit))
+        .filter(|&single| access.contains(single))
+        .filter(|single| !single.supported_pipeline_stages().intersects(stages))
+        .fold(BufferAccess::empty(), |acc, single| acc | single)
+}
@@ -2,6 +2,7 @@ use hal::image::{Access as ImageAccess, Layout as ImageLayout, Usage as ImageUsa
 use hal::pso::PipelineStage;
 
 use resource::{Access, Layout, Usage};
+use stage::all_commands;
 
 impl Access for ImageAccess {
     fn none() -> Self {
@@ -19,6 +20,10 @@ impl Access for ImageAccess {
             || self.contains(Self::MEMORY_WRITE)
     }
 
+    fn is_generic(&self) -> bool {
+        self.intersects(Self::MEMORY_READ | Self::MEMORY_WRITE)
+    }
+
     fn is_read(&self) -> bool {
         self.contains(Self::COLOR_ATTACHMENT_READ)
             || self.contains(Self::DEPTH_STENCIL_ATTACHMENT_READ)
@@ -44,7 +49,7 @@ impl Access for ImageAccess {
                 PS::EARLY_FRAGMENT_TESTS | PS::LATE_FRAGMENT_TESTS
             }
             Self::HOST_READ | Self::HOST_WRITE => PS::HOST,
-            Self::MEMORY_READ | Self::MEMORY_WRITE => PS::empty(),
+            Self::MEMORY_READ | Self::MEMORY_WRITE => all_commands(),
             Self::INPUT_ATTACHMENT_READ => PS::FRAGMENT_SHADER,
             _ => panic!("Only one bit must be set"),
         }
@@ -77,3 +82,149 @@ impl Usage for ImageUsage {
         ImageUsage::all()
     }
 }
+
+/// Derive image usage flags required for access.
+pub(crate) fn derive_usage(access: ImageAccess) -> ImageUsage {
+    const TABLE: &[(ImageAccess, ImageUsage)] = &[
+        (ImageAccess::TRANSFER_READ, ImageUsage::TRANSFER_SRC),
+        (ImageAccess::TRANSFER_WRITE, ImageUsage::TRANSFER_DST),
+        (
+            ImageAccess::COLOR_ATTACHMENT_READ,
+            ImageUsage::COLOR_ATTACHMENT,
+        ),
+        (
+            ImageAccess::COLOR_ATTACHMENT_WRITE,
+            ImageUsage::COLOR_ATTACHMENT,
+        ),
+        (
+            ImageAccess::DEPTH_STENCIL_ATTACHMENT_READ,
+            ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+        ),
+        (
+            ImageAccess::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+        ),
+        (
+            ImageAccess::INPUT_ATTACHMENT_READ,
+            ImageUsage::INPUT_ATTACHMENT,
+        ),
+        // Shader reads can be either sampled or storage, so they derive nothing.
+        (ImageAccess::SHADER_WRITE, ImageUsage::STORAGE),
+    ];
+
+    TABLE
+        .iter()
+        .filter(|&&(flag, _)| access.contains(flag))
+        .fold(ImageUsage::empty(), |acc, &(_, usage)| acc | usage)
+}
+
+/// Get access types through which image bound with the usage can be accessed.
+pub(crate) fn usage_access(usage: ImageUsage) -> ImageAccess {
+    const TABLE: &[(ImageUsage, ImageAccess)] = &[
+        (ImageUsage::TRANSFER_SRC, ImageAccess::TRANSFER_READ),
+        (ImageUsage::TRANSFER_DST, ImageAccess::TRANSFER_WRITE),
+        (ImageUsage::SAMPLED, ImageAccess::SHADER_READ),
+        (ImageUsage::STORAGE, ImageAccess::SHADER_READ),
+        (ImageUsage::STORAGE, ImageAccess::SHADER_WRITE),
+        (
+            ImageUsage::COLOR_ATTACHMENT,
+            ImageAccess::COLOR_ATTACHMENT_READ,
+        ),
+        (
+            ImageUsage::COLOR_ATTACHMENT,
+            ImageAccess::COLOR_ATTACHMENT_WRITE,
+        ),
+        (
+            ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+            ImageAccess::DEPTH_STENCIL_ATTACHMENT_READ,
+        ),
+        (
+            ImageUsage::DEPTH_STENCIL_ATTACHMENT,
+            ImageAccess::DEPTH_STENCIL_ATTACHMENT_WRITE,
+        ),
+        (
+            ImageUsage::INPUT_ATTACHMENT,
+            ImageAccess::INPUT_ATTACHMENT_READ,
+        ),
+    ];
+
+    TABLE
+        .iter()
+        .filter(|&&(flag, _)| usage.contains(flag))
+        .fold(ImageAccess::empty(), |acc, &(_, access)| acc | access)
+}
+
+/// Get access types not supported by any of the stages.
+pub(crate) fn unsupported_access(access: ImageAccess, stages: PipelineStage) -> ImageAccess {
+    (0..32)
+        .filter_map(|bit| ImageAccess::from_bits(1 << bit))
+        .filter(|&single| access.contains(single))
+        .filter(|single| !single.supported_pipeline_stages().intersects(stages))
+        .fold(ImageAccess::empty(), |acc, single| acc | single)
+}
+
+/// Check if image can be accessed as specified while in the layout.
+/// Generic memory writes are supported only in `General` layout
+/// and generic memory reads in `General` and read-only layouts.
+pub(crate) fn layout_supports(layout: ImageLayout, access: ImageAccess) -> bool {
+    type IA = ImageAccess;
+    type IL = ImageLayout;
+
+    (0..32)
+        .filter_map(|bit| IA::from_bits(1 << bit))
+        .filter(|&single| access.contains(single))
+        .all(|single| {
+            matches!(
+                (single, layout),
+                (IA::MEMORY_READ, IL::ShaderReadOnlyOptimal)
+                    | (IA::MEMORY_READ, IL::DepthStencilReadOnlyOptimal)
+                    | (IA::MEMORY_READ, IL::TransferSrcOptimal)
+                    | (IA::HOST_READ, IL::Preinitialized)
+                    | (IA::HOST_WRITE, IL::Preinitialized)
+                    | (_, IL::General)
+                    | (IA::COLOR_ATTACHMENT_READ, IL::ColorAttachmentOptimal)
+                    | (IA::COLOR_ATTACHMENT_WRITE, IL::ColorAttachmentOptimal)
+                    | (
+                        IA::DEPTH_STENCIL_ATTACHMENT_READ,
+                        IL::DepthStencilAttachmentOptimal
+                    )
+                    | (
+                        IA::DEPTH_STENCIL_ATTACHMENT_READ,
+                        IL::DepthStencilReadOnlyOptimal
+                    )
+                    | (
+                        IA::DEPTH_STENCIL_ATTACHMENT_WRITE,
+                        IL::DepthStencilAttachmentOptimal
+                    )
+                    | (IA::SHADER_READ, IL::ShaderReadOnlyOptimal)
+                    | (IA::SHADER_READ, IL::DepthStencilReadOnlyOptimal)
+                    | (IA::INPUT_ATTACHMENT_READ, IL::ShaderReadOnlyOptimal)
+                    | (IA::INPUT_ATTACHMENT_READ, IL::DepthStencilReadOnlyOptimal)
+                    | (IA::TRANSFER_READ, IL::TransferSrcOptimal)
+                    | (IA::TRANSFER_WRITE, IL::TransferDstOptimal)
+            )
+        })
+}
+
+/// Get layout optimal for access.
+/// Returns `None` if no layout other than `General` supports all access types.
+/// Generic memory access alone doesn't pick a layout.
+pub(crate) fn optimal_layout(access: ImageAccess) -> Option<ImageLayout> {
+    const LAYOUTS: &[ImageLayout] = &[
+        ImageLayout::ColorAttachmentOptimal,
+        ImageLayout::ShaderReadOnlyOptimal,
+        ImageLayout::DepthStencilReadOnlyOptimal,
+        ImageLayout::DepthStencilAttachmentOptimal,
+        ImageLayout::TransferSrcOptimal,
+        ImageLayout::TransferDstOptimal,
+    ];
+
+    if (access - (ImageAccess::MEMORY_READ | ImageAccess::MEMORY_WRITE)).is_empty() {
+        return None;
+    }
+
+    LAYOUTS
+        .iter()
+        .cloned()
+        .find(|&layout| layout_supports(layout, access))
+}
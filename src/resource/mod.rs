@@ -26,6 +26,11 @@ pub use self::usage::Usage;
 
 /// Defines resource type.
 /// Should be implemented for buffers and images.
+///
+/// `State`, `Link` and `Chain` can be used with any type implementing this trait.
+/// `Access` implementation must be able to tell read and write accesses apart,
+/// and `Layout` implementation must merge layouts compatible for simultaneous use.
+/// But `Pass`, `collect` and `sync` only track `Buffer` and `Image` resources.
 pub trait Resource: Copy + Debug + Eq + Ord + Hash {
     /// Access type of the resource.
     type Access: Access;
@@ -38,6 +43,27 @@ pub trait Resource: Copy + Debug + Eq + Ord + Hash {
 
     /// Sub-resource range.
     type Range: Clone;
+
+    /// Derive usage flags required by resource to be accessed as specified.
+    /// Default implementation derives no usage flags.
+    fn derive_usage(access: Self::Access) -> Self::Usage {
+        let _ = access;
+        Self::Usage::none()
+    }
+
+    /// Get access types through which resource bound with specified usage can be accessed.
+    /// Default implementation allows any access.
+    fn usage_access(usage: Self::Usage) -> Self::Access {
+        let _ = usage;
+        Self::Access::all()
+    }
+
+    /// Check if resource can be accessed as specified while in the layout.
+    /// Default implementation allows any access in any layout.
+    fn layout_supports(layout: Self::Layout, access: Self::Access) -> bool {
+        let _ = (layout, access);
+        true
+    }
 }
 
 /// Buffer resource.
@@ -49,6 +75,14 @@ impl Resource for Buffer {
     type Layout = buffer::BufferLayout;
     type Usage = BufferUsage;
     type Range = Range<u64>;
+
+    fn derive_usage(access: BufferAccess) -> BufferUsage {
+        buffer::derive_usage(access)
+    }
+
+    fn usage_access(usage: BufferUsage) -> BufferAccess {
+        buffer::usage_access(usage)
+    }
 }
 
 /// Image resource.
@@ -60,6 +94,18 @@ impl Resource for Image {
     type Layout = ImageLayout;
     type Usage = ImageUsage;
     type Range = SubresourceRange;
+
+    fn derive_usage(access: ImageAccess) -> ImageUsage {
+        image::derive_usage(access)
+    }
+
+    fn usage_access(usage: ImageUsage) -> ImageAccess {
+        image::usage_access(usage)
+    }
+
+    fn layout_supports(layout: ImageLayout, access: ImageAccess) -> bool {
+        image::layout_supports(layout, access)
+    }
 }
 
 /// Resource typed id
@@ -110,9 +156,46 @@ where
         self.access.is_write()
     }
 
+    /// Check if state only transitions resource to its layout.
+    /// Such states have no access.
+    pub fn transition(&self) -> bool {
+        self.access == R::Access::none()
+    }
+
     /// Check if states are compatible.
     /// This requires layouts to be compatible and non-exclusive access.
+    /// Transition states are compatible with nothing so transitions are never merged.
     pub fn compatible(&self, rhs: Self) -> bool {
-        !self.exclusive() && !rhs.exclusive() && self.layout.merge(rhs.layout).is_some()
+        !self.exclusive()
+            && !rhs.exclusive()
+            && !self.transition()
+            && !rhs.transition()
+            && self.layout.merge(rhs.layout).is_some()
+    }
+}
+
+impl State<Buffer> {
+    /// Get access types of the state that aren't supported by any of its stages.
+    /// Non-empty result means that state declaration is inconsistent.
+    pub fn unsupported_access(&self) -> BufferAccess {
+        buffer::unsupported_access(self.access, self.stages)
+    }
+}
+
+impl State<Image> {
+    /// Get access types of the state that aren't supported by any of its stages.
+    /// Non-empty result means that state declaration is inconsistent.
+    pub fn unsupported_access(&self) -> ImageAccess {
+        image::unsupported_access(self.access, self.stages)
+    }
+
+    /// Get layout optimal for access of the state
+    /// if the layout of the state is `General` or doesn't support the access.
+    /// `General` layout is legal for most access types but may hurt performance.
+    pub fn suggest_layout(&self) -> Option<ImageLayout> {
+        if self.layout != ImageLayout::General && image::layout_supports(self.layout, self.access) {
+            return None;
+        }
+        image::optimal_layout(self.access).filter(|&layout| layout != self.layout)
     }
 }
@@ -56,7 +56,13 @@ impl<S> Family<S> {
     /// This function will panic if requested queue isn't part of this family.
     ///
     pub fn queue(&self, qid: QueueId) -> Option<&Queue<S>> {
-        assert_eq!(self.id, qid.family());
+        assert_eq!(
+            self.id,
+            qid.family(),
+            "{:?} isn't part of family {:?}",
+            qid,
+            self.id
+        );
         self.queues.get(qid.index())
     }
 
@@ -67,10 +73,26 @@ impl<S> Family<S> {
     /// This function will panic if requested queue isn't part of this family.
     ///
     pub fn queue_mut(&mut self, qid: QueueId) -> Option<&mut Queue<S>> {
-        assert_eq!(self.id, qid.family());
+        assert_eq!(
+            self.id,
+            qid.family(),
+            "{:?} isn't part of family {:?}",
+            qid,
+            self.id
+        );
         self.queues.get_mut(qid.index())
     }
 
+    /// Get reference to `Queue` instance by the id.
+    /// Returns `None` if requested queue isn't part of this family.
+    pub fn try_queue(&self, qid: QueueId) -> Option<&Queue<S>> {
+        if self.id == qid.family() {
+            self.queues.get(qid.index())
+        } else {
+            None
+        }
+    }
+
     /// Get mutable reference to `Queue` instance by the id.
     /// This function will grow queues array if index is out of bounds.
     ///
@@ -79,7 +101,13 @@ impl<S> Family<S> {
     /// This function will panic if requested queue isn't part of this family.
     ///
     pub fn ensure_queue(&mut self, qid: QueueId) -> &mut Queue<S> {
-        assert_eq!(self.id, qid.family());
+        assert_eq!(
+            self.id,
+            qid.family(),
+            "{:?} isn't part of family {:?}",
+            qid,
+            self.id
+        );
         let len = self.queues.len();
         self.queues
             .extend((len..qid.index() + 1).map(|i| Queue::new(QueueId::new(qid.family(), i))));
@@ -93,11 +121,24 @@ impl<S> Family<S> {
     /// This function will panic if requested submission isn't part of this family.
     ///
     pub fn submission(&self, sid: SubmissionId) -> Option<&Submission<S>> {
-        assert_eq!(self.id, sid.family());
+        assert_eq!(
+            self.id,
+            sid.family(),
+            "{:?} isn't part of family {:?}",
+            sid,
+            self.id
+        );
         self.queue(sid.queue())
             .and_then(|queue| queue.submission(sid))
     }
 
+    /// Get reference to `Submission<S>` instance by id.
+    /// Returns `None` if requested submission isn't part of this family.
+    pub fn try_submission(&self, sid: SubmissionId) -> Option<&Submission<S>> {
+        self.try_queue(sid.queue())
+            .and_then(|queue| queue.try_submission(sid))
+    }
+
     /// Get mutable reference to `Submission<S>` instance by id.
     ///
     /// # Panic
@@ -105,7 +146,13 @@ impl<S> Family<S> {
     /// This function will panic if requested submission isn't part of this family.
     ///
     pub fn submission_mut(&mut self, sid: SubmissionId) -> Option<&mut Submission<S>> {
-        assert_eq!(self.id, sid.family());
+        assert_eq!(
+            self.id,
+            sid.family(),
+            "{:?} isn't part of family {:?}",
+            sid,
+            self.id
+        );
         self.queue_mut(sid.queue())
             .and_then(|queue| queue.submission_mut(sid))
     }
@@ -142,13 +189,29 @@ impl<S> Index<QueueId> for Family<S> {
     type Output = Queue<S>;
 
     fn index(&self, qid: QueueId) -> &Queue<S> {
-        self.queue(qid).unwrap()
+        let count = self.queues.len();
+        self.queue(qid).unwrap_or_else(|| {
+            panic!(
+                "{:?} is out of range. Family {:?} has {} queues",
+                qid,
+                qid.family(),
+                count
+            )
+        })
     }
 }
 
 impl<S> IndexMut<QueueId> for Family<S> {
     fn index_mut(&mut self, qid: QueueId) -> &mut Queue<S> {
-        self.queue_mut(qid).unwrap()
+        let count = self.queues.len();
+        self.queue_mut(qid).unwrap_or_else(|| {
+            panic!(
+                "{:?} is out of range. Family {:?} has {} queues",
+                qid,
+                qid.family(),
+                count
+            )
+        })
     }
 }
 
@@ -156,12 +219,12 @@ impl<S> Index<SubmissionId> for Family<S> {
     type Output = Submission<S>;
 
     fn index(&self, sid: SubmissionId) -> &Submission<S> {
-        self.submission(sid).unwrap()
+        &self[sid.queue()][sid]
     }
 }
 
 impl<S> IndexMut<SubmissionId> for Family<S> {
     fn index_mut(&mut self, sid: SubmissionId) -> &mut Submission<S> {
-        self.submission_mut(sid).unwrap()
+        &mut self[sid.queue()][sid]
     }
 }
@@ -16,10 +16,16 @@ use fnv::FnvHashMap;
 use std::collections::hash_map::{
     IntoIter as HashMapIntoIter, Values as HashMapValues, ValuesMut as HashMapValuesMut,
 };
+use std::error::Error;
+use std::fmt;
 use std::ops::{Index, IndexMut};
 
 use hal::queue::QueueFamilyId;
 
+use chain::Chain;
+use pass::PassId;
+use resource::Resource;
+
 pub use self::family::Family;
 pub use self::queue::{Queue, QueueId, QueueIter, QueueIterMut};
 pub use self::submission::{Submission, SubmissionId};
@@ -108,8 +114,99 @@ impl<S> Schedule<S> {
         self.queue_mut(sid.queue())
             .and_then(|queue| queue.submission_mut(sid))
     }
+
+    /// Find submission of the pass.
+    /// Each pass is scheduled to exactly one submission on one queue,
+    /// so all synchronization for the pass is recorded into command buffer for that queue.
+    pub fn submission_by_pass(&self, pass: PassId) -> Option<(SubmissionId, &Submission<S>)> {
+        self.iter()
+            .flat_map(|family| family.iter())
+            .flat_map(|queue| queue.iter())
+            .find(|&(_, submission)| submission.pass() == pass)
+    }
+
+    /// Get number of queues required for each family to execute the schedule.
+    /// That is the index of the last queue with submissions plus one.
+    pub fn queue_requirements(&self) -> FnvHashMap<QueueFamilyId, usize> {
+        self.iter()
+            .filter_map(|family| {
+                family
+                    .iter()
+                    .rposition(|queue| queue.len() > 0)
+                    .map(|index| (family.id(), index + 1))
+            })
+            .collect()
+    }
+
+    /// Check that all queues of the schedule with submissions exist on the device.
+    ///
+    /// # Parameters
+    ///
+    /// `family_count`  - number of queue families of the device.
+    /// `max_queues`    - function that returns number of queues for specified family.
+    ///
+    pub fn validate_device<Q>(
+        &self,
+        family_count: usize,
+        max_queues: Q,
+    ) -> Result<(), DeviceMismatch>
+    where
+        Q: Fn(QueueFamilyId) -> usize,
+    {
+        for family in self.iter() {
+            // Queues without submissions are not required to exist.
+            if family.iter().all(|queue| queue.len() == 0) {
+                continue;
+            }
+            if family.id().0 >= family_count {
+                return Err(DeviceMismatch::Family(family.id()));
+            }
+            let queue_count = max_queues(family.id());
+            if let Some(queue) = family
+                .iter()
+                .filter(|queue| queue.len() > 0)
+                .find(|queue| queue.id().index() >= queue_count)
+            {
+                return Err(DeviceMismatch::Queue(queue.id()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Find submission that writes resource of the chain last.
+    /// Returns `None` if resource is never written.
+    pub fn last_writer_submission<R>(&self, chain: &Chain<R>) -> Option<SubmissionId>
+    where
+        R: Resource,
+    {
+        let link = chain.link(chain.last_writer()?);
+        link.queues()
+            .map(|(qid, queue)| SubmissionId::new(qid, queue.last))
+            .max_by_key(|&sid| self[sid].submit_order())
+    }
+}
+
+/// Error returned by `Schedule::validate_device`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceMismatch {
+    /// Device has no such family.
+    Family(QueueFamilyId),
+
+    /// Family of the device has no such queue.
+    Queue(QueueId),
 }
 
+impl fmt::Display for DeviceMismatch {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeviceMismatch::Family(fid) => write!(fmt, "Device has no family {:?}", fid),
+            DeviceMismatch::Queue(qid) => write!(fmt, "Device has no queue {:?}", qid),
+        }
+    }
+}
+
+impl Error for DeviceMismatch {}
+
 /// Iterator over owned families in this schedule
 pub struct ScheduleIntoIter<S>(HashMapIntoIter<QueueFamilyId, Family<S>>);
 
@@ -161,13 +258,15 @@ impl<S> Index<QueueFamilyId> for Schedule<S> {
     type Output = Family<S>;
 
     fn index(&self, fid: QueueFamilyId) -> &Family<S> {
-        self.family(fid).unwrap()
+        self.family(fid)
+            .unwrap_or_else(|| panic!("Family {:?} is not in the schedule", fid))
     }
 }
 
 impl<S> IndexMut<QueueFamilyId> for Schedule<S> {
     fn index_mut(&mut self, fid: QueueFamilyId) -> &mut Family<S> {
-        self.family_mut(fid).unwrap()
+        self.family_mut(fid)
+            .unwrap_or_else(|| panic!("Family {:?} is not in the schedule", fid))
     }
 }
 
@@ -175,13 +274,13 @@ impl<S> Index<QueueId> for Schedule<S> {
     type Output = Queue<S>;
 
     fn index(&self, qid: QueueId) -> &Queue<S> {
-        self.queue(qid).unwrap()
+        &self[qid.family()][qid]
     }
 }
 
 impl<S> IndexMut<QueueId> for Schedule<S> {
     fn index_mut(&mut self, qid: QueueId) -> &mut Queue<S> {
-        self.queue_mut(qid).unwrap()
+        &mut self[qid.family()][qid]
     }
 }
 
@@ -189,12 +288,12 @@ impl<S> Index<SubmissionId> for Schedule<S> {
     type Output = Submission<S>;
 
     fn index(&self, sid: SubmissionId) -> &Submission<S> {
-        self.submission(sid).unwrap()
+        &self[sid.queue()][sid]
     }
 }
 
 impl<S> IndexMut<SubmissionId> for Schedule<S> {
     fn index_mut(&mut self, sid: SubmissionId) -> &mut Submission<S> {
-        self.submission_mut(sid).unwrap()
+        &mut self[sid.queue()][sid]
     }
 }
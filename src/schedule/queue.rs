@@ -185,10 +185,26 @@ impl<S> Queue<S> {
     /// This function will panic if requested submission isn't part of this queue.
     ///
     pub fn submission(&self, sid: SubmissionId) -> Option<&Submission<S>> {
-        assert_eq!(self.id, sid.queue());
+        assert_eq!(
+            self.id,
+            sid.queue(),
+            "{:?} isn't part of queue {:?}",
+            sid,
+            self.id
+        );
         self.submissions.get(sid.index())
     }
 
+    /// Get reference to `Submission` instance by id.
+    /// Returns `None` if requested submission isn't part of this queue.
+    pub fn try_submission(&self, sid: SubmissionId) -> Option<&Submission<S>> {
+        if self.id == sid.queue() {
+            self.submissions.get(sid.index())
+        } else {
+            None
+        }
+    }
+
     /// Get mutable reference to `Submission` instance by id.
     ///
     /// # Panic
@@ -196,7 +212,13 @@ impl<S> Queue<S> {
     /// This function will panic if requested submission isn't part of this queue.
     ///
     pub fn submission_mut(&mut self, sid: SubmissionId) -> Option<&mut Submission<S>> {
-        assert_eq!(self.id, sid.queue());
+        assert_eq!(
+            self.id,
+            sid.queue(),
+            "{:?} isn't part of queue {:?}",
+            sid,
+            self.id
+        );
         self.submissions.get_mut(sid.index())
     }
 
@@ -249,12 +271,28 @@ impl<S> Index<SubmissionId> for Queue<S> {
     type Output = Submission<S>;
 
     fn index(&self, sid: SubmissionId) -> &Submission<S> {
-        self.submission(sid).unwrap()
+        let count = self.submissions.len();
+        self.submission(sid).unwrap_or_else(|| {
+            panic!(
+                "{:?} is out of range. Queue {:?} has {} submissions",
+                sid,
+                sid.queue(),
+                count
+            )
+        })
     }
 }
 
 impl<S> IndexMut<SubmissionId> for Queue<S> {
     fn index_mut(&mut self, sid: SubmissionId) -> &mut Submission<S> {
-        self.submission_mut(sid).unwrap()
+        let count = self.submissions.len();
+        self.submission_mut(sid).unwrap_or_else(|| {
+            panic!(
+                "{:?} is out of range. Queue {:?} has {} submissions",
+                sid,
+                sid.queue(),
+                count
+            )
+        })
     }
 }
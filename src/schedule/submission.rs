@@ -60,6 +60,11 @@ impl<S> Submission<S> {
         &self.sync
     }
 
+    /// Get synchronization for `Submission`.
+    pub(crate) fn sync_mut(&mut self) -> &mut S {
+        &mut self.sync
+    }
+
     /// Get wait factor for `Submission`
     pub fn wait_factor(&self) -> usize {
         self.wait_factor
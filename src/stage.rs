@@ -0,0 +1,166 @@
+//!
+//! This module provides functions to reason about logical order of pipeline stages.
+//! Stages are ordered as specified by Vulkan for graphics, compute and transfer commands.
+//! Stages from different pipelines (e.g. `COMPUTE_SHADER` and `FRAGMENT_SHADER`) are not ordered.
+//!
+
+use hal::pso::PipelineStage;
+use hal::queue::QueueType;
+
+/// Logical order of stages for graphics commands.
+const GRAPHICS: &[PipelineStage] = &[
+    PipelineStage::TOP_OF_PIPE,
+    PipelineStage::DRAW_INDIRECT,
+    PipelineStage::VERTEX_INPUT,
+    PipelineStage::VERTEX_SHADER,
+    PipelineStage::HULL_SHADER,
+    PipelineStage::DOMAIN_SHADER,
+    PipelineStage::GEOMETRY_SHADER,
+    PipelineStage::EARLY_FRAGMENT_TESTS,
+    PipelineStage::FRAGMENT_SHADER,
+    PipelineStage::LATE_FRAGMENT_TESTS,
+    PipelineStage::COLOR_ATTACHMENT_OUTPUT,
+    PipelineStage::BOTTOM_OF_PIPE,
+];
+
+/// Logical order of stages for compute commands.
+const COMPUTE: &[PipelineStage] = &[
+    PipelineStage::TOP_OF_PIPE,
+    PipelineStage::DRAW_INDIRECT,
+    PipelineStage::COMPUTE_SHADER,
+    PipelineStage::BOTTOM_OF_PIPE,
+];
+
+/// Logical order of stages for transfer commands.
+const TRANSFER: &[PipelineStage] = &[
+    PipelineStage::TOP_OF_PIPE,
+    PipelineStage::TRANSFER,
+    PipelineStage::BOTTOM_OF_PIPE,
+];
+
+const ORDERS: &[&[PipelineStage]] = &[GRAPHICS, COMPUTE, TRANSFER];
+
+/// Get stages of all commands that can be recorded into command buffer.
+pub fn all_commands() -> PipelineStage {
+    PipelineStage::all() & !PipelineStage::HOST
+}
+
+/// Get stages that can be used in commands submitted to queues of the type.
+/// `HOST` stage is included as it can be used by synchronization commands on any queue.
+pub fn supported_by(queue_type: QueueType) -> PipelineStage {
+    type PS = PipelineStage;
+
+    let transfer = PS::TOP_OF_PIPE | PS::TRANSFER | PS::BOTTOM_OF_PIPE | PS::HOST;
+    let compute = transfer | PS::DRAW_INDIRECT | PS::COMPUTE_SHADER;
+    let graphics = GRAPHICS.iter().fold(transfer, |acc, &stage| acc | stage);
+
+    match queue_type {
+        QueueType::General => graphics | compute,
+        QueueType::Graphics => graphics,
+        QueueType::Compute => compute,
+        QueueType::Transfer => transfer,
+    }
+}
+
+/// Iterate over single stages from the mask.
+pub fn single_stages(mask: PipelineStage) -> impl Iterator<Item = PipelineStage> {
+    (0..32)
+        .filter_map(|bit| PipelineStage::from_bits(1 << bit))
+        .filter(move |&stage| mask.contains(stage))
+}
+
+/// Check if stage `a` is logically earlier than stage `b`.
+/// Returns `None` if stages are not ordered or if `a` or `b` isn't a single stage.
+pub fn is_earlier(a: PipelineStage, b: PipelineStage) -> Option<bool> {
+    if a.bits().count_ones() != 1 || b.bits().count_ones() != 1 {
+        return None;
+    }
+
+    if a == b {
+        return Some(false);
+    }
+
+    ORDERS
+        .iter()
+        .filter_map(|order| {
+            let a = order.iter().position(|&stage| stage == a)?;
+            let b = order.iter().position(|&stage| stage == b)?;
+            Some(a < b)
+        })
+        .next()
+}
+
+/// Get stages from the mask that have no logically earlier stages in the mask.
+/// Result has single stage if all stages in the mask are ordered.
+pub fn earliest(mask: PipelineStage) -> PipelineStage {
+    single_stages(mask)
+        .filter(|&stage| single_stages(mask).all(|other| is_earlier(other, stage) != Some(true)))
+        .fold(PipelineStage::empty(), |acc, stage| acc | stage)
+}
+
+/// Get stages from the mask that have no logically later stages in the mask.
+/// Result has single stage if all stages in the mask are ordered.
+pub fn latest(mask: PipelineStage) -> PipelineStage {
+    single_stages(mask)
+        .filter(|&stage| single_stages(mask).all(|other| is_earlier(stage, other) != Some(true)))
+        .fold(PipelineStage::empty(), |acc, stage| acc | stage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type PS = PipelineStage;
+
+    #[test]
+    fn is_earlier_follows_orders() {
+        for order in ORDERS {
+            for (i, &a) in order.iter().enumerate() {
+                for (j, &b) in order.iter().enumerate() {
+                    assert_eq!(is_earlier(a, b), Some(i < j), "{:?} and {:?}", a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn is_earlier_unordered() {
+        assert_eq!(is_earlier(PS::COMPUTE_SHADER, PS::FRAGMENT_SHADER), None);
+        assert_eq!(is_earlier(PS::TRANSFER, PS::VERTEX_SHADER), None);
+        assert_eq!(is_earlier(PS::HOST, PS::TOP_OF_PIPE), None);
+    }
+
+    #[test]
+    fn is_earlier_multiple_stages() {
+        let mask = PS::VERTEX_SHADER | PS::FRAGMENT_SHADER;
+        assert_eq!(is_earlier(mask, PS::BOTTOM_OF_PIPE), None);
+        assert_eq!(is_earlier(PS::TOP_OF_PIPE, mask), None);
+        assert_eq!(is_earlier(PS::empty(), PS::TOP_OF_PIPE), None);
+    }
+
+    #[test]
+    fn earliest_and_latest_ordered() {
+        let mask = PS::VERTEX_SHADER | PS::FRAGMENT_SHADER | PS::COLOR_ATTACHMENT_OUTPUT;
+        assert_eq!(earliest(mask), PS::VERTEX_SHADER);
+        assert_eq!(latest(mask), PS::COLOR_ATTACHMENT_OUTPUT);
+        assert_eq!(earliest(all_commands()), PS::TOP_OF_PIPE);
+        assert_eq!(latest(all_commands()), PS::BOTTOM_OF_PIPE);
+    }
+
+    #[test]
+    fn earliest_and_latest_unordered() {
+        let mask = PS::FRAGMENT_SHADER | PS::COMPUTE_SHADER;
+        assert_eq!(earliest(mask), mask);
+        assert_eq!(latest(mask), mask);
+
+        let mask = PS::DRAW_INDIRECT | PS::FRAGMENT_SHADER | PS::COMPUTE_SHADER;
+        assert_eq!(earliest(mask), PS::DRAW_INDIRECT);
+        assert_eq!(latest(mask), PS::FRAGMENT_SHADER | PS::COMPUTE_SHADER);
+    }
+
+    #[test]
+    fn earliest_and_latest_empty() {
+        assert_eq!(earliest(PS::empty()), PS::empty());
+        assert_eq!(latest(PS::empty()), PS::empty());
+    }
+}
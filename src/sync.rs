@@ -2,22 +2,23 @@
 //! This crates provide functions for find all required synchronizations (barriers and semaphores).
 //!
 
-use fnv::FnvHashMap;
-use std::cmp::Ordering;
+use fnv::{FnvHashMap, FnvHashSet};
+use std::cmp::{max, Ordering};
+use std::error::Error;
+use std::fmt;
 use std::ops::{Range, RangeFrom, RangeTo};
 
 use hal::pso::PipelineStage;
+use hal::queue::{QueueFamilyId, QueueType};
 
-use chain::{Chain, Link};
+use chain::{Chain, Link, OfChain};
 use collect::{Chains, Unsynchronized};
-use resource::{Access, Buffer, Id, Image, Resource, State};
+use pass::PassId;
+use resource::{Access, Buffer, Id, Image, Layout, Resource, State};
 use schedule::{QueueId, Schedule, SubmissionId};
+use stage::{self, all_commands, supported_by};
 use Pick;
 
-// fn earlier_stage(stages: PipelineStage) -> PipelineStage {
-//     PipelineStage::from_bits((stages.bits() - 1) ^ (stages.bits())).unwrap()
-// }
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Uid {
     Buffer(u32),
@@ -38,7 +39,7 @@ impl From<Id<Image>> for Uid {
 
 /// Side of the submission. `Acquire` or `Release`.
 #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
-enum Side {
+pub enum Side {
     /// Acquire side of the submission.
     /// Synchronization commands from this side must be recorded before main commands of submission.
     Acquire,
@@ -135,12 +136,21 @@ impl<S> Wait<S> {
     }
 }
 
+/// Id of the ownership transfer.
+/// Release and acquire barriers of the same transfer share the id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TransferId(pub usize);
+
 /// Pipeline barrier info.
 #[derive(Clone, Debug)]
 pub struct Barrier<R: Resource> {
-    /// `Some` queue for ownership transfer. Or `None`
+    /// `Some` queue for ownership transfer.
+    /// Or `None` if queue families must be ignored.
     pub queues: Option<Range<QueueId>>,
 
+    /// `Some` id for ownership transfer. Or `None`
+    pub transfer: Option<TransferId>,
+
     /// Stage transition.
     pub states: Range<State<R>>,
 }
@@ -150,25 +160,36 @@ where
     R: Resource,
 {
     fn new(states: Range<State<R>>) -> Self {
-        Barrier {
+        let mut barrier = Barrier {
             queues: None,
+            transfer: None,
             states,
+        };
+        if !barrier.needs_memory_barrier() {
+            // Execution dependency implicitly covers logically earlier source stages
+            // and logically later destination stages.
+            barrier.states.start.stages = stage::latest(barrier.states.start.stages);
+            barrier.states.end.stages = stage::earliest(barrier.states.end.stages);
         }
+        barrier
     }
 
-    fn transfer(queues: Range<QueueId>, states: Range<State<R>>) -> Self {
+    fn transfer(transfer: TransferId, queues: Range<QueueId>, states: Range<State<R>>) -> Self {
         Barrier {
             queues: Some(queues),
+            transfer: Some(transfer),
             states,
         }
     }
 
     fn acquire(
+        transfer: TransferId,
         queues: Range<QueueId>,
         left: RangeFrom<R::Layout>,
         right: RangeTo<State<R>>,
     ) -> Self {
         Self::transfer(
+            transfer,
             queues,
             State {
                 access: R::Access::none(),
@@ -179,11 +200,13 @@ where
     }
 
     fn release(
+        transfer: TransferId,
         queues: Range<QueueId>,
         left: RangeFrom<State<R>>,
         right: RangeTo<R::Layout>,
     ) -> Self {
         Self::transfer(
+            transfer,
             queues,
             left.start..State {
                 access: R::Access::none(),
@@ -192,6 +215,37 @@ where
             },
         )
     }
+
+    /// Get source and destination stages of the barrier.
+    pub fn stages(&self) -> Range<PipelineStage> {
+        self.states.start.stages..self.states.end.stages
+    }
+
+    /// Get old and new layouts of the barrier.
+    /// Old layout is `Layout::discard_content` if content of the resource is discarded by the barrier.
+    pub fn layouts(&self) -> Range<R::Layout> {
+        self.states.start.layout..self.states.end.layout
+    }
+
+    /// Check if this barrier requires memory dependency.
+    /// Barrier that doesn't make writes available and doesn't transition layout
+    /// (e.g. write-after-read) requires only execution dependency.
+    /// Such barrier can be recorded with empty access masks.
+    ///
+    /// Each half of ownership transfer carries only its own access.
+    /// Transfer with empty access masks on both halves and no layout transition
+    /// only changes ownership of resource that is only read.
+    /// It is still recorded as resource barrier between queue families.
+    pub fn needs_memory_barrier(&self) -> bool {
+        let (start, end) = (&self.states.start, &self.states.end);
+        if start.layout != end.layout {
+            return true;
+        }
+        match self.queues {
+            Some(_) => start.access != R::Access::none() || end.access != R::Access::none(),
+            None => start.access.is_write(),
+        }
+    }
 }
 
 /// Map of barriers by resource id.
@@ -203,6 +257,194 @@ pub type BufferBarriers = Barriers<Buffer>;
 /// Map of barriers by image id.
 pub type ImageBarriers = Barriers<Image>;
 
+/// Condition that matched for a boundary between two links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoundaryCondition {
+    /// Links are on the same family or resource is shared concurrently.
+    /// Previous link is on single queue while next link spans many.
+    /// Barrier is recorded at the release side of the previous link.
+    SameFamilySingleToMultiQueue,
+
+    /// Links are on the same family or resource is shared concurrently.
+    /// Barrier is recorded at the acquire side of the next link.
+    SameFamily,
+
+    /// Links are on the same family or resource is shared concurrently.
+    /// Both links only read the resource with the same access types in the same layout
+    /// and share no queues.
+    /// No barrier is recorded.
+    SharedRead,
+
+    /// Links are on different families.
+    /// Ownership of the resource is transferred.
+    FamilyTransfer,
+}
+
+/// Synchronization chosen for a boundary between two links.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BoundarySync {
+    /// Only pipeline barrier.
+    /// All submissions are on the same queue or already ordered by other semaphores.
+    Barrier,
+
+    /// Pipeline barrier and semaphores between queues.
+    BarrierSemaphore,
+
+    /// Only semaphores between queues.
+    Semaphore,
+
+    /// Release and acquire barriers with semaphore between them.
+    Transfer,
+
+    /// No synchronization.
+    /// Links only read the resource and are already ordered by other semaphores.
+    None,
+}
+
+/// Explanation of synchronization generated between two adjacent links of a chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoundaryDecision {
+    /// Indices of the previous and the next link.
+    pub links: Range<usize>,
+
+    /// Condition that matched.
+    pub condition: BoundaryCondition,
+
+    /// Synchronization chosen.
+    pub sync: BoundarySync,
+}
+
+/// Decision for a boundary with semaphores generated for it.
+/// Some of semaphores may be removed later as redundant.
+struct PendingDecision {
+    links: Range<usize>,
+    condition: BoundaryCondition,
+    semaphores: Vec<Semaphore>,
+}
+
+impl PendingDecision {
+    /// Choose synchronization by semaphores that are left in the schedule.
+    fn resolve(self, remaining: &FnvHashSet<&Semaphore>) -> BoundaryDecision {
+        let semaphores = self
+            .semaphores
+            .iter()
+            .any(|semaphore| remaining.contains(semaphore));
+        BoundaryDecision {
+            links: self.links,
+            condition: self.condition,
+            sync: match self.condition {
+                BoundaryCondition::FamilyTransfer => BoundarySync::Transfer,
+                BoundaryCondition::SharedRead if semaphores => BoundarySync::Semaphore,
+                BoundaryCondition::SharedRead => BoundarySync::None,
+                _ if semaphores => BoundarySync::BarrierSemaphore,
+                _ => BoundarySync::Barrier,
+            },
+        }
+    }
+}
+
+/// Decisions made for each chain by `sync_explained`.
+#[derive(Clone, Debug)]
+pub struct Explanation {
+    /// Decisions for buffer chains.
+    pub buffers: FnvHashMap<Id<Buffer>, Vec<BoundaryDecision>>,
+
+    /// Decisions for image chains.
+    pub images: FnvHashMap<Id<Image>, Vec<BoundaryDecision>>,
+}
+
+/// Statistics of synchronization generated for a chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChainStats {
+    /// Number of semaphore pairs.
+    pub semaphores: usize,
+
+    /// Number of pipeline barriers.
+    /// Ownership transfer counts as two barriers. Release and acquire.
+    pub barriers: usize,
+
+    /// Number of ownership transfers.
+    pub transfers: usize,
+}
+
+impl ChainStats {
+    fn add(&mut self, other: &ChainStats) {
+        self.semaphores += other.semaphores;
+        self.barriers += other.barriers;
+        self.transfers += other.transfers;
+    }
+}
+
+/// Statistics collected by `sync_with_stats`.
+#[derive(Clone, Debug)]
+pub struct BuildStats {
+    /// Statistics for buffer chains.
+    pub buffers: FnvHashMap<Id<Buffer>, ChainStats>,
+
+    /// Statistics for image chains.
+    pub images: FnvHashMap<Id<Image>, ChainStats>,
+
+    /// Statistics for all chains combined.
+    pub total: ChainStats,
+}
+
+impl BuildStats {
+    fn collect<S>(chains: &Chains<S>, sync: &SyncTemp) -> Self {
+        let mut stats = BuildStats {
+            buffers: chains
+                .buffers
+                .keys()
+                .map(|&id| (id, ChainStats::default()))
+                .collect(),
+            images: chains
+                .images
+                .keys()
+                .map(|&id| (id, ChainStats::default()))
+                .collect(),
+            total: ChainStats::default(),
+        };
+
+        for data in sync.0.values() {
+            for &(guard, side) in &[
+                (&data.acquire, Side::Acquire),
+                (&data.release, Side::Release),
+            ] {
+                for signal in &guard.signal {
+                    stats.chain(signal.semaphore().id).semaphores += 1;
+                }
+                for (&id, barrier) in &guard.buffers {
+                    stats.count_barrier(id.into(), barrier.queues.is_some(), side);
+                }
+                for (&id, barrier) in &guard.images {
+                    stats.count_barrier(id.into(), barrier.queues.is_some(), side);
+                }
+            }
+        }
+
+        let mut total = ChainStats::default();
+        for chain in stats.buffers.values().chain(stats.images.values()) {
+            total.add(chain);
+        }
+        stats.total = total;
+        stats
+    }
+
+    fn chain(&mut self, uid: Uid) -> &mut ChainStats {
+        match uid {
+            Uid::Buffer(index) => self.buffers.entry(Id::new(index)).or_default(),
+            Uid::Image(index) => self.images.entry(Id::new(index)).or_default(),
+        }
+    }
+
+    fn count_barrier(&mut self, uid: Uid, transfer: bool, side: Side) {
+        let chain = self.chain(uid);
+        chain.barriers += 1;
+        if transfer && side == Side::Release {
+            chain.transfers += 1;
+        }
+    }
+}
+
 /// Synchronization for submission at one side.
 #[derive(Clone, Debug)]
 pub struct Guard<S, W> {
@@ -215,6 +457,11 @@ pub struct Guard<S, W> {
     /// Image pipeline barriers to be inserted before or after (depends on the side) main commands of the submission.
     pub images: ImageBarriers,
 
+    /// Stages of global memory barrier from all memory writes to all memory accesses
+    /// that must be inserted along with barriers above. Empty if there is none.
+    /// Set by `Guard::coarsen` to all stages supported by the queue.
+    pub global: PipelineStage,
+
     /// Points at other queues that can run after barriers above.
     pub signal: Vec<Signal<S>>,
 }
@@ -225,9 +472,39 @@ impl<S, W> Guard<S, W> {
             wait: Vec::new(),
             buffers: FnvHashMap::default(),
             images: FnvHashMap::default(),
+            global: PipelineStage::empty(),
             signal: Vec::new(),
         }
     }
+
+    /// Replace barriers that only make memory available and visible with global memory barrier.
+    /// Barriers for ownership transfers and layout transitions are kept.
+    /// Useful for debugging and as a fallback.
+    /// `queue_type` is type of the queue to which submission is submitted.
+    pub fn coarsen(&mut self, queue_type: QueueType) {
+        let buffers = self.buffers.len();
+        let images = self.images.len();
+        self.buffers.retain(|_, barrier| barrier.queues.is_some());
+        self.images.retain(|_, barrier| {
+            barrier.queues.is_some() || barrier.states.start.layout != barrier.states.end.layout
+        });
+        if buffers != self.buffers.len() || images != self.images.len() {
+            self.global = all_commands() & supported_by(queue_type);
+        }
+    }
+
+    /// Get union of source and destination stages of all barriers in the guard.
+    /// Barriers can be recorded with single pipeline barrier command with those stages.
+    pub fn stages(&self) -> Range<PipelineStage> {
+        let global = self.global..self.global;
+        self.buffers
+            .values()
+            .map(Barrier::stages)
+            .chain(self.images.values().map(Barrier::stages))
+            .fold(global, |acc, stages| {
+                acc.start | stages.start..acc.end | stages.end
+            })
+    }
 }
 
 impl<S, W> Pick<Image> for Guard<S, W> {
@@ -294,6 +571,7 @@ impl<S, W> SyncData<S, W> {
                     .collect(),
                 buffers: self.acquire.buffers,
                 images: self.acquire.images,
+                global: self.acquire.global,
             },
             release: Guard {
                 wait: self.release.wait,
@@ -305,6 +583,7 @@ impl<S, W> SyncData<S, W> {
                     .collect(),
                 buffers: self.release.buffers,
                 images: self.release.images,
+                global: self.release.global,
             },
         }
     }
@@ -324,6 +603,7 @@ impl<S, W> SyncData<S, W> {
                 signal: self.acquire.signal,
                 buffers: self.acquire.buffers,
                 images: self.acquire.images,
+                global: self.acquire.global,
             },
             release: Guard {
                 wait: self
@@ -335,69 +615,844 @@ impl<S, W> SyncData<S, W> {
                 signal: self.release.signal,
                 buffers: self.release.buffers,
                 images: self.release.images,
+                global: self.release.global,
             },
         }
     }
 }
 
-struct SyncTemp(FnvHashMap<SubmissionId, SyncData<Semaphore, Semaphore>>);
+/// Barrier for a resource with its place in execution.
+#[derive(Clone, Debug)]
+pub struct TimedBarrier<R: Resource> {
+    /// Submission in which barrier must be recorded.
+    pub sid: SubmissionId,
+
+    /// Pass of the submission.
+    pub pass: PassId,
+
+    /// Side of the submission at which barrier must be recorded.
+    pub side: Side,
+
+    /// Sequence number.
+    /// Barriers of all resources can be sorted by it to form single stream in execution order.
+    pub sequence: usize,
+
+    /// The barrier.
+    pub barrier: Barrier<R>,
+}
+
+/// Ownership transfer of a resource between queue families.
+#[derive(Clone, Debug)]
+pub struct OwnershipTransfer<R: Resource> {
+    /// Id of the resource.
+    pub id: Id<R>,
+
+    /// Id that pairs release and acquire barriers of the transfer.
+    pub transfer: TransferId,
+
+    /// Release barrier recorded on the source queue.
+    pub release: Option<TimedBarrier<R>>,
+
+    /// Acquire barrier recorded on the destination queue.
+    pub acquire: Option<TimedBarrier<R>>,
+}
+
+impl<R> OwnershipTransfer<R>
+where
+    R: Resource,
+{
+    /// Check if transfer has both release and acquire barriers.
+    pub fn balanced(&self) -> bool {
+        self.release.is_some() && self.acquire.is_some()
+    }
+}
+
+impl<S, W> Schedule<SyncData<S, W>> {
+    /// Get all barriers for the buffer in execution order.
+    pub fn buffer_barriers(&self, id: Id<Buffer>) -> Vec<TimedBarrier<Buffer>> {
+        flatten(self, id)
+    }
+
+    /// Get all barriers for the image in execution order.
+    pub fn image_barriers(&self, id: Id<Image>) -> Vec<TimedBarrier<Image>> {
+        flatten(self, id)
+    }
+
+    /// Get all ownership transfers of buffers ordered by transfer id.
+    pub fn buffer_transfers(&self) -> Vec<OwnershipTransfer<Buffer>> {
+        transfers(self)
+    }
+
+    /// Get all ownership transfers of images ordered by transfer id.
+    pub fn image_transfers(&self) -> Vec<OwnershipTransfer<Image>> {
+        transfers(self)
+    }
+
+    /// Check if every ownership transfer has both release and acquire barriers.
+    pub fn transfers_balanced(&self) -> bool {
+        self.buffer_transfers()
+            .iter()
+            .all(OwnershipTransfer::balanced)
+            && self
+                .image_transfers()
+                .iter()
+                .all(OwnershipTransfer::balanced)
+    }
+
+    /// Make the first submission of the chain wait for external semaphore.
+    /// Useful to wait for other subsystem that produces content of the resource.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if chain has no links or if the first link spans multiple queues.
+    ///
+    pub fn external_acquire<R>(&mut self, chain: &Chain<R>, semaphore: W, stages: PipelineStage)
+    where
+        R: Resource,
+    {
+        let link = chain.links().first().expect("Chain has no links");
+        assert!(
+            link.single_queue(),
+            "External semaphore can't be waited by link{} on multiple queues",
+            OfChain(chain.name())
+        );
+        let sid = earliest(link, self);
+        self[sid]
+            .sync_mut()
+            .acquire
+            .wait
+            .push(Wait::new(semaphore, stages));
+    }
+
+    /// Make the last submission of the chain signal external semaphore.
+    /// Useful to notify other subsystem that consumes content of the resource.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if chain has no links or if the last link spans multiple queues.
+    ///
+    pub fn external_release<R>(&mut self, chain: &Chain<R>, semaphore: S)
+    where
+        R: Resource,
+    {
+        let link = chain.links().last().expect("Chain has no links");
+        assert!(
+            link.single_queue(),
+            "External semaphore can't be signaled by link{} on multiple queues",
+            OfChain(chain.name())
+        );
+        let sid = latest(link, self);
+        self[sid]
+            .sync_mut()
+            .release
+            .signal
+            .push(Signal::new(semaphore));
+    }
+
+    /// Replace barriers recorded before commands of the pass with global memory barrier.
+    /// `queue_type` returns type of queues of the family.
+    /// See `Guard::coarsen`.
+    pub fn coarsen_barriers<Q>(&mut self, pass: PassId, queue_type: Q)
+    where
+        Q: Fn(QueueFamilyId) -> QueueType,
+    {
+        for queue in self.iter_mut().flat_map(|family| family.iter_mut()) {
+            for (sid, submission) in queue.iter_mut() {
+                if submission.pass() == pass {
+                    submission
+                        .sync_mut()
+                        .acquire
+                        .coarsen(queue_type(sid.family()));
+                }
+            }
+        }
+    }
+}
+
+fn flatten<R, S, W>(schedule: &Schedule<SyncData<S, W>>, id: Id<R>) -> Vec<TimedBarrier<R>>
+where
+    R: Resource,
+    Guard<S, W>: Pick<R, Target = Barriers<R>>,
+{
+    let mut barriers = Vec::new();
+    for queue in schedule.iter().flat_map(|family| family.iter()) {
+        for (sid, submission) in queue.iter() {
+            let sync = submission.sync();
+            for &(guard, side) in &[
+                (&sync.acquire, Side::Acquire),
+                (&sync.release, Side::Release),
+            ] {
+                if let Some(barrier) = guard.pick().get(&id) {
+                    barriers.push(TimedBarrier {
+                        sid,
+                        pass: submission.pass(),
+                        side,
+                        sequence: submission.submit_order() * 2 + side as usize,
+                        barrier: barrier.clone(),
+                    });
+                }
+            }
+        }
+    }
+    barriers.sort_by_key(|barrier| barrier.sequence);
+    barriers
+}
+
+fn transfers<R, S, W>(schedule: &Schedule<SyncData<S, W>>) -> Vec<OwnershipTransfer<R>>
+where
+    R: Resource,
+    Guard<S, W>: Pick<R, Target = Barriers<R>>,
+{
+    let mut transfers: FnvHashMap<TransferId, OwnershipTransfer<R>> = FnvHashMap::default();
+    for queue in schedule.iter().flat_map(|family| family.iter()) {
+        for (sid, submission) in queue.iter() {
+            let sync = submission.sync();
+            for &(guard, side) in &[
+                (&sync.acquire, Side::Acquire),
+                (&sync.release, Side::Release),
+            ] {
+                for (&id, barrier) in guard.pick() {
+                    let transfer = match barrier.transfer {
+                        Some(transfer) => transfer,
+                        None => continue,
+                    };
+                    let entry = transfers
+                        .entry(transfer)
+                        .or_insert_with(|| OwnershipTransfer {
+                            id,
+                            transfer,
+                            release: None,
+                            acquire: None,
+                        });
+                    debug_assert_eq!(entry.id, id);
+                    let timed = Some(TimedBarrier {
+                        sid,
+                        pass: submission.pass(),
+                        side,
+                        sequence: submission.submit_order() * 2 + side as usize,
+                        barrier: barrier.clone(),
+                    });
+                    match side {
+                        Side::Acquire => entry.acquire = timed,
+                        Side::Release => entry.release = timed,
+                    }
+                }
+            }
+        }
+    }
+    let mut transfers: Vec<_> = transfers.into_values().collect();
+    transfers.sort_by_key(|transfer| transfer.transfer);
+    transfers
+}
+
+/// Synchronization for submissions and the number of ownership transfers generated.
+struct SyncTemp(
+    FnvHashMap<SubmissionId, SyncData<Semaphore, Semaphore>>,
+    usize,
+);
 impl SyncTemp {
     fn get_sync(&mut self, sid: SubmissionId) -> &mut SyncData<Semaphore, Semaphore> {
         self.0.entry(sid).or_insert_with(|| SyncData::new())
     }
+
+    fn new_transfer(&mut self) -> TransferId {
+        self.1 += 1;
+        TransferId(self.1 - 1)
+    }
+}
+
+/// Synchronization for all submissions with semaphores replaced by slot indices.
+/// Template can be instantiated multiple times with different semaphores.
+/// E.g. once for each frame in flight.
+#[derive(Debug)]
+pub struct SyncTemplate {
+    schedule: Schedule<SyncData<usize, usize>>,
+    semaphores: usize,
+}
+
+impl SyncTemplate {
+    /// Get schedule with semaphore slot indices.
+    /// Signal and wait with the same index refer to the same semaphore.
+    pub fn schedule(&self) -> &Schedule<SyncData<usize, usize>> {
+        &self.schedule
+    }
+
+    /// Get number of semaphores required to instantiate template.
+    pub fn semaphore_count(&self) -> usize {
+        self.semaphores
+    }
+
+    /// Create synchronized schedule filling slots with new semaphores.
+    /// `new_semaphore` is called exactly `semaphore_count` times.
+    pub fn instantiate<F, S, W>(&self, mut new_semaphore: F) -> Schedule<SyncData<S, W>>
+    where
+        F: FnMut() -> (S, W),
+    {
+        self.instantiate_indexed(|_| new_semaphore())
+    }
+
+    /// Create synchronized schedule filling slots with new semaphores.
+    /// `new_semaphore` is called once for each slot in order with index of the slot.
+    /// Slot indices are stable for identical input, so they can be used to index semaphore pool.
+    pub fn instantiate_indexed<F, S, W>(&self, mut new_semaphore: F) -> Schedule<SyncData<S, W>>
+    where
+        F: FnMut(usize) -> (S, W),
+    {
+        let mut signals = Vec::with_capacity(self.semaphores);
+        let mut waits = Vec::with_capacity(self.semaphores);
+        for slot in 0..self.semaphores {
+            let (signal, wait) = new_semaphore(slot);
+            signals.push(Some(signal));
+            waits.push(Some(wait));
+        }
+
+        let mut result = Schedule::default();
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            let new_queue = result.ensure_queue(queue.id());
+            for (sid, submission) in queue.iter() {
+                let sync = submission
+                    .sync()
+                    .clone()
+                    .convert_signal(|slot| signals[slot].take().unwrap())
+                    .convert_wait(|slot| waits[slot].take().unwrap());
+                let new_sid = new_queue.add_submission(submission.set_sync(sync));
+                assert_eq!(sid, new_sid);
+            }
+        }
+
+        debug_assert!(signals.iter().all(Option::is_none));
+        debug_assert!(waits.iter().all(Option::is_none));
+
+        result
+    }
+
+    /// Assign semaphore objects to slots so that slots which lifetimes don't overlap share an object.
+    /// Lifetime of a slot starts when semaphore is signaled and ends when it is waited.
+    /// Slot reuses an object only if it is signaled after the object's last wait.
+    /// Returns number of objects required and object index for each slot.
+    pub fn semaphore_objects(&self) -> (usize, Vec<usize>) {
+        let mut order = vec![0; self.semaphores];
+        let mut signals = vec![None; self.semaphores];
+        let mut waits = vec![None; self.semaphores];
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            for (sid, submission) in queue.iter() {
+                let sync = submission.sync();
+                for &(guard, side) in &[
+                    (&sync.acquire, Side::Acquire),
+                    (&sync.release, Side::Release),
+                ] {
+                    for signal in &guard.signal {
+                        order[*signal.semaphore()] = submission.submit_order() * 2 + side as usize;
+                        signals[*signal.semaphore()] = Some(Point::new(sid, side));
+                    }
+                    for wait in &guard.wait {
+                        waits[*wait.semaphore()] = Some(Point::new(sid, side));
+                    }
+                }
+            }
+        }
+
+        let mut slots: Vec<usize> = (0..self.semaphores).collect();
+        slots.sort_by_key(|&slot| order[slot]);
+
+        // Point at which each object is waited last.
+        let mut free: Vec<Option<Point>> = Vec::new();
+        let mut objects = vec![0; self.semaphores];
+        for slot in slots {
+            let reusable = |wait: &Option<Point>| match (*wait, signals[slot]) {
+                (Some(wait), Some(signal)) if wait.sid == signal.sid => wait.side <= signal.side,
+                (Some(wait), Some(signal)) => self.reachable(wait.sid, signal.sid),
+                _ => false,
+            };
+            let object = match free.iter().position(reusable) {
+                Some(object) => object,
+                None => {
+                    free.push(None);
+                    free.len() - 1
+                }
+            };
+            free[object] = waits[slot];
+            objects[slot] = object;
+        }
+
+        (free.len(), objects)
+    }
+
+    /// Replace semaphores with timeline values.
+    /// Each submission signals its index in the queue plus one on the queue's timeline.
+    /// Each semaphore wait becomes wait for the value signaled by the submission that signals it.
+    ///
+    /// Timeline is signaled only when submission is complete,
+    /// so waits for semaphores signaled on the acquire side wait longer than required.
+    /// Waits for the same queue from both sides of submission are merged.
+    pub fn timeline(&self) -> Schedule<TimelineSync> {
+        let mut signaled: FnvHashMap<usize, SubmissionId> = FnvHashMap::default();
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            for (sid, submission) in queue.iter() {
+                let sync = submission.sync();
+                for signal in sync.acquire.signal.iter().chain(&sync.release.signal) {
+                    signaled.insert(*signal.semaphore(), sid);
+                }
+            }
+        }
+
+        let mut result = Schedule::default();
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            let new_queue = result.ensure_queue(queue.id());
+            for (sid, submission) in queue.iter() {
+                let sync = submission.sync();
+                let mut waits: Vec<TimelineWait> = Vec::new();
+                for wait in sync.acquire.wait.iter().chain(&sync.release.wait) {
+                    let signal = signaled[wait.semaphore()];
+                    let value = signal.index() as u64 + 1;
+                    match waits.iter().position(|w| w.queue == signal.queue()) {
+                        Some(index) => {
+                            let existing = &mut waits[index];
+                            existing.value = max(existing.value, value);
+                            existing.stages |= wait.stage();
+                        }
+                        None => waits.push(TimelineWait {
+                            queue: signal.queue(),
+                            value,
+                            stages: wait.stage(),
+                        }),
+                    }
+                }
+                let timeline = TimelineSync {
+                    signal: sid.index() as u64 + 1,
+                    waits,
+                };
+                let new_sid = new_queue.add_submission(submission.set_sync(timeline));
+                assert_eq!(sid, new_sid);
+            }
+        }
+        result
+    }
+
+    /// Get minimal set of submissions to wait with fences
+    /// to ensure that all submissions are complete.
+    /// That is last submissions of queues which completion is not waited
+    /// by submissions on other queues.
+    pub fn resource_reuse_fences(&self) -> Vec<SubmissionId> {
+        let mut signaled: FnvHashMap<usize, SubmissionId> = FnvHashMap::default();
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            for (sid, submission) in queue.iter() {
+                // Semaphores signaled on the acquire side don't guarantee submission completion.
+                for signal in &submission.sync().release.signal {
+                    signaled.insert(*signal.semaphore(), sid);
+                }
+            }
+        }
+
+        // Queues which last submission completes before a submission on another queue.
+        let mut joined = FnvHashSet::default();
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            for (_, submission) in queue.iter() {
+                let sync = submission.sync();
+                for wait in sync.acquire.wait.iter().chain(&sync.release.wait) {
+                    let signal = signaled.get(wait.semaphore());
+                    if let Some(&signal) = signal {
+                        if signal.index() + 1 == self.schedule[signal.queue()].len() {
+                            joined.insert(signal.queue());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut fences: Vec<_> = self
+            .schedule
+            .iter()
+            .flat_map(|family| family.iter())
+            .filter(|queue| !joined.contains(&queue.id()))
+            .filter_map(|queue| queue.iter().next_back().map(|(sid, _)| sid))
+            .collect();
+        fences.sort();
+        fences
+    }
+
+    /// Check that every semaphore slot is signaled exactly once and waited exactly once.
+    pub fn validate_semaphore_balance(&self) -> Result<(), UnmatchedSemaphore> {
+        let mut counts = vec![(0, 0); self.semaphores];
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            for (_, submission) in queue.iter() {
+                let sync = submission.sync();
+                for signal in sync.acquire.signal.iter().chain(&sync.release.signal) {
+                    counts[*signal.semaphore()].0 += 1;
+                }
+                for wait in sync.acquire.wait.iter().chain(&sync.release.wait) {
+                    counts[*wait.semaphore()].1 += 1;
+                }
+            }
+        }
+        match counts.iter().position(|&count| count != (1, 1)) {
+            Some(slot) => Err(UnmatchedSemaphore {
+                slot,
+                signals: counts[slot].0,
+                waits: counts[slot].1,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Check if passes can be executed concurrently.
+    /// That is passes are on different queues and neither waits for the other,
+    /// directly or through other submissions.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if any of passes is not in the schedule.
+    pub fn can_overlap(&self, a: PassId, b: PassId) -> bool {
+        let a = self.find_pass(a);
+        let b = self.find_pass(b);
+        a.queue() != b.queue() && !self.reachable(a, b) && !self.reachable(b, a)
+    }
+
+    fn find_pass(&self, pass: PassId) -> SubmissionId {
+        self.schedule
+            .submission_by_pass(pass)
+            .map(|(sid, _)| sid)
+            .unwrap_or_else(|| panic!("{:?} is not in the schedule", pass))
+    }
+
+    /// Check if submission `to` can't start before submission `from` is started.
+    fn reachable(&self, from: SubmissionId, to: SubmissionId) -> bool {
+        let mut waits: FnvHashMap<usize, Vec<SubmissionId>> = FnvHashMap::default();
+        for queue in self.schedule.iter().flat_map(|family| family.iter()) {
+            for (sid, submission) in queue.iter() {
+                let sync = submission.sync();
+                for wait in sync.acquire.wait.iter().chain(&sync.release.wait) {
+                    waits.entry(*wait.semaphore()).or_default().push(sid);
+                }
+            }
+        }
+
+        let mut visited = FnvHashSet::default();
+        visited.insert(from);
+        let mut stack = vec![from];
+        while let Some(sid) = stack.pop() {
+            if sid == to {
+                return true;
+            }
+            let sync = self.schedule[sid].sync();
+            let next = SubmissionId::new(sid.queue(), sid.index() + 1);
+            let next = self.schedule.submission(next).map(|_| next);
+            let signaled = sync
+                .acquire
+                .signal
+                .iter()
+                .chain(&sync.release.signal)
+                .flat_map(|signal| waits.get(signal.semaphore()))
+                .flat_map(|sids| sids.iter().cloned());
+            for sid in next.into_iter().chain(signaled) {
+                if visited.insert(sid) {
+                    stack.push(sid);
+                }
+            }
+        }
+        false
+    }
+
+    /// Estimate time each queue spends idle waiting for semaphores.
+    /// `cost` returns execution time of the submission.
+    ///
+    /// Submissions start as soon as previous submission on the queue is complete
+    /// and semaphores waited on the acquire side are signaled.
+    /// Semaphores waited on the release side delay completion of the submission.
+    pub fn estimate_bubbles<F>(&self, mut cost: F) -> Vec<(QueueId, u64)>
+    where
+        F: FnMut(SubmissionId) -> u64,
+    {
+        let mut submissions: Vec<_> = self
+            .schedule
+            .iter()
+            .flat_map(|family| family.iter())
+            .flat_map(|queue| queue.iter())
+            .collect();
+        submissions.sort_by_key(|&(_, submission)| submission.submit_order());
+
+        let mut signaled: FnvHashMap<usize, u64> = FnvHashMap::default();
+        let mut queues: FnvHashMap<QueueId, (u64, u64)> = self
+            .schedule
+            .iter()
+            .flat_map(|family| family.iter())
+            .map(|queue| (queue.id(), (0, 0)))
+            .collect();
+
+        for (sid, submission) in submissions {
+            let sync = submission.sync();
+            let wait_time = |guard: &Guard<usize, usize>| {
+                guard
+                    .wait
+                    .iter()
+                    .map(|wait| signaled[wait.semaphore()])
+                    .max()
+                    .unwrap_or(0)
+            };
+
+            let &mut (ref mut free, ref mut idle) = queues.get_mut(&sid.queue()).unwrap();
+            let start = max(*free, wait_time(&sync.acquire));
+            let executed = start + cost(sid);
+            let end = max(executed, wait_time(&sync.release));
+            *idle += (start - *free) + (end - executed);
+            *free = end;
+
+            for signal in &sync.acquire.signal {
+                signaled.insert(*signal.semaphore(), start);
+            }
+            for signal in &sync.release.signal {
+                signaled.insert(*signal.semaphore(), end);
+            }
+        }
+
+        let mut bubbles: Vec<_> = queues
+            .into_iter()
+            .map(|(qid, (_, idle))| (qid, idle))
+            .collect();
+        bubbles.sort();
+        bubbles
+    }
+}
+
+/// Error returned by `SyncTemplate::validate_semaphore_balance`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnmatchedSemaphore {
+    /// Semaphore slot.
+    pub slot: usize,
+
+    /// Number of signals of the semaphore.
+    pub signals: usize,
+
+    /// Number of waits of the semaphore.
+    pub waits: usize,
+}
+
+impl fmt::Display for UnmatchedSemaphore {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "Semaphore {} is signaled {} times and waited {} times",
+            self.slot, self.signals, self.waits
+        )
+    }
+}
+
+impl Error for UnmatchedSemaphore {}
+
+/// Synchronization of submission with one timeline semaphore per queue.
+#[derive(Clone, Debug)]
+pub struct TimelineSync {
+    /// Value to signal on the queue's timeline when submission is complete.
+    pub signal: u64,
+
+    /// Values to wait on timelines of other queues with destination stages.
+    pub waits: Vec<TimelineWait>,
+}
+
+/// Wait for value on timeline of the queue.
+#[derive(Clone, Debug)]
+pub struct TimelineWait {
+    /// Queue which timeline to wait.
+    pub queue: QueueId,
+
+    /// Value to wait.
+    pub value: u64,
+
+    /// Stages that must wait.
+    pub stages: PipelineStage,
 }
 
 /// Find required synchronization for all submissions in `Chains`.
-pub fn sync<F, S, W>(
+/// Conservative chains wait for all commands.
+pub fn sync<F, S, W>(chains: &Chains<Unsynchronized>, new_semaphore: F) -> Schedule<SyncData<S, W>>
+where
+    F: FnMut() -> (S, W),
+{
+    sync_with_queue_types(chains, |_| QueueType::General, new_semaphore)
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// `queue_type` returns type of queues of the family.
+/// It limits stages used for conservative chains to ones supported by the queue.
+pub fn sync_with_queue_types<F, Q, S, W>(
     chains: &Chains<Unsynchronized>,
-    mut new_semaphore: F,
+    queue_type: Q,
+    new_semaphore: F,
 ) -> Schedule<SyncData<S, W>>
 where
+    Q: Fn(QueueFamilyId) -> QueueType,
     F: FnMut() -> (S, W),
+{
+    sync_template_with_queue_types(chains, queue_type).instantiate(new_semaphore)
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// Semaphores are left as slots to be filled by `SyncTemplate::instantiate`.
+pub fn sync_template(chains: &Chains<Unsynchronized>) -> SyncTemplate {
+    sync_template_with_queue_types(chains, |_| QueueType::General)
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// Semaphores are left as slots to be filled by `SyncTemplate::instantiate`.
+/// See `sync_with_queue_types` for `queue_type` description.
+pub fn sync_template_with_queue_types<Q>(
+    chains: &Chains<Unsynchronized>,
+    queue_type: Q,
+) -> SyncTemplate
+where
+    Q: Fn(QueueFamilyId) -> QueueType,
+{
+    sync_impl(chains, &queue_type, false, false).0
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// Additionally returns decisions made for each boundary between links.
+pub fn sync_explained<F, S, W>(
+    chains: &Chains<Unsynchronized>,
+    new_semaphore: F,
+) -> (Schedule<SyncData<S, W>>, Explanation)
+where
+    F: FnMut() -> (S, W),
+{
+    sync_explained_with_queue_types(chains, |_| QueueType::General, new_semaphore)
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// Additionally returns decisions made for each boundary between links.
+/// See `sync_with_queue_types` for `queue_type` description.
+pub fn sync_explained_with_queue_types<F, Q, S, W>(
+    chains: &Chains<Unsynchronized>,
+    queue_type: Q,
+    new_semaphore: F,
+) -> (Schedule<SyncData<S, W>>, Explanation)
+where
+    Q: Fn(QueueFamilyId) -> QueueType,
+    F: FnMut() -> (S, W),
+{
+    let (template, explanation, _) = sync_impl(chains, &queue_type, true, false);
+    (template.instantiate(new_semaphore), explanation.unwrap())
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// Additionally returns statistics of synchronization generated for each chain.
+pub fn sync_with_stats<F, S, W>(
+    chains: &Chains<Unsynchronized>,
+    new_semaphore: F,
+) -> (Schedule<SyncData<S, W>>, BuildStats)
+where
+    F: FnMut() -> (S, W),
+{
+    sync_with_stats_with_queue_types(chains, |_| QueueType::General, new_semaphore)
+}
+
+/// Find required synchronization for all submissions in `Chains`.
+/// Additionally returns statistics of synchronization generated for each chain.
+/// See `sync_with_queue_types` for `queue_type` description.
+pub fn sync_with_stats_with_queue_types<F, Q, S, W>(
+    chains: &Chains<Unsynchronized>,
+    queue_type: Q,
+    new_semaphore: F,
+) -> (Schedule<SyncData<S, W>>, BuildStats)
+where
+    Q: Fn(QueueFamilyId) -> QueueType,
+    F: FnMut() -> (S, W),
+{
+    let (template, _, stats) = sync_impl(chains, &queue_type, false, true);
+    (template.instantiate(new_semaphore), stats.unwrap())
+}
+
+/// Decisions and statistics are only gathered when requested.
+fn sync_impl<Q>(
+    chains: &Chains<Unsynchronized>,
+    queue_type: &Q,
+    explain: bool,
+    collect_stats: bool,
+) -> (SyncTemplate, Option<Explanation>, Option<BuildStats>)
+where
+    Q: Fn(QueueFamilyId) -> QueueType,
 {
     let ref schedule = chains.schedule;
     let ref buffers = chains.buffers;
     let ref images = chains.images;
 
-    let mut sync = SyncTemp(FnvHashMap::default());
+    let mut sync = SyncTemp(FnvHashMap::default(), 0);
+    let mut buffer_decisions = Vec::new();
+    let mut image_decisions = Vec::new();
+
+    // Chains are processed in order of ids, and queues in order of their ids,
+    // so that identical input always produces identical output.
+    // That includes order of semaphores, transfer ids and semaphore slots.
+    let mut buffers: Vec<_> = buffers.iter().collect();
+    buffers.sort_by_key(|&(&id, _)| id);
+    let mut images: Vec<_> = images.iter().collect();
+    images.sort_by_key(|&(&id, _)| id);
+
     for (&id, chain) in buffers {
-        sync_chain(id, chain, schedule, &mut sync);
+        let decisions = sync_chain(id, chain, schedule, queue_type, &mut sync, explain);
+        if explain {
+            buffer_decisions.push((id, decisions));
+        }
     }
     for (&id, chain) in images {
-        sync_chain(id, chain, schedule, &mut sync);
+        let decisions = sync_chain(id, chain, schedule, queue_type, &mut sync, explain);
+        if explain {
+            image_decisions.push((id, decisions));
+        }
     }
 
     if schedule.queue_count() > 1 {
         optimize(schedule, &mut sync);
     }
 
+    // Decisions are resolved against semaphores left after optimization.
+    let explanation = if explain {
+        let remaining: FnvHashSet<&Semaphore> = sync
+            .0
+            .values()
+            .flat_map(|data| data.acquire.wait.iter().chain(&data.release.wait))
+            .map(|wait| wait.semaphore())
+            .collect();
+        let resolve = |decisions: Vec<PendingDecision>| {
+            decisions
+                .into_iter()
+                .map(|decision| decision.resolve(&remaining))
+                .collect()
+        };
+        Some(Explanation {
+            buffers: buffer_decisions
+                .into_iter()
+                .map(|(id, decisions)| (id, resolve(decisions)))
+                .collect(),
+            images: image_decisions
+                .into_iter()
+                .map(|(id, decisions)| (id, resolve(decisions)))
+                .collect(),
+        })
+    } else {
+        None
+    };
+
+    let stats = if collect_stats {
+        Some(BuildStats::collect(chains, &sync))
+    } else {
+        None
+    };
+
     let mut result = Schedule::default();
-    let mut signals: FnvHashMap<Semaphore, Option<S>> = FnvHashMap::default();
-    let mut waits: FnvHashMap<Semaphore, Option<W>> = FnvHashMap::default();
+    let mut slots: FnvHashMap<Semaphore, usize> = FnvHashMap::default();
 
-    for queue in schedule.iter().flat_map(|family| family.iter()) {
+    let mut queues: Vec<_> = schedule.iter().flat_map(|family| family.iter()).collect();
+    queues.sort_by_key(|queue| queue.id());
+
+    for queue in queues {
         let new_queue = result.ensure_queue(queue.id());
         for (sid, submission) in queue.iter() {
             let sync = if let Some(sync) = sync.0.remove(&sid) {
-                let sync = sync.convert_signal(|semaphore| match signals.get_mut(&semaphore) {
-                    None => {
-                        let (signal, wait) = new_semaphore();
-                        let old = waits.insert(semaphore, Some(wait));
-                        assert!(old.is_none());
-                        signal
-                    }
-                    Some(signal) => signal.take().unwrap(),
-                });
-                let sync = sync.convert_wait(|semaphore| match waits.get_mut(&semaphore) {
-                    None => {
-                        let (signal, wait) = new_semaphore();
-                        let old = signals.insert(semaphore, Some(signal));
-                        assert!(old.is_none());
-                        wait
-                    }
-                    Some(wait) => wait.take().unwrap(),
-                });
-                sync
+                let mut slot = |semaphore| {
+                    let next = slots.len();
+                    *slots.entry(semaphore).or_insert(next)
+                };
+                sync.convert_signal(&mut slot).convert_wait(&mut slot)
             } else {
                 SyncData::new()
             };
@@ -407,10 +1462,12 @@ where
     }
 
     debug_assert!(sync.0.is_empty());
-    debug_assert!(signals.values().all(|x| x.is_none()));
-    debug_assert!(waits.values().all(|x| x.is_none()));
 
-    result
+    let template = SyncTemplate {
+        schedule: result,
+        semaphores: slots.len(),
+    };
+    (template, explanation, stats)
 }
 
 // submit_order creates a consistant direction in which semaphores are generated, avoiding issues
@@ -444,13 +1501,18 @@ where
     sid
 }
 
-fn generate_semaphore_pair<R: Resource>(
+fn generate_semaphore_pair<R, C>(
     sync: &mut SyncTemp,
     id: Uid,
     link: &Link<R>,
+    conservative: &C,
     range: Range<SubmissionId>,
     sides: Range<Side>,
-) {
+) -> Option<Semaphore>
+where
+    R: Resource,
+    C: Fn(&Link<R>, QueueFamilyId) -> Option<PipelineStage>,
+{
     let points = Point::new(range.start, sides.start)..Point::new(range.end, sides.end);
     if points.start.sid.queue() != points.end.sid.queue() {
         let semaphore = Semaphore::new(id, points.clone());
@@ -458,153 +1520,333 @@ fn generate_semaphore_pair<R: Resource>(
             .get_mut(points.start.side)
             .signal
             .push(Signal::new(semaphore.clone()));
+        // Logically later stages wait along with the earliest ones.
+        let stages = conservative(link, points.end.sid.family())
+            .unwrap_or_else(|| stage::earliest(link.queue(points.end.sid.queue()).stages));
         sync.get_sync(points.end.sid)
             .get_mut(points.end.side)
             .wait
-            .push(Wait::new(
-                semaphore,
-                link.queue(points.end.sid.queue()).stages,
-            ));
+            .push(Wait::new(semaphore.clone(), stages));
+        Some(semaphore)
+    } else {
+        None
+    }
+}
+
+/// Get condition that matches for a boundary between two adjacent links of the chain.
+pub(crate) fn boundary_condition<R>(
+    chain: &Chain<R>,
+    prev_link: &Link<R>,
+    link: &Link<R>,
+) -> BoundaryCondition
+where
+    R: Resource,
+{
+    if prev_link.family() == link.family() || chain.concurrent() {
+        let shared_read = !prev_link.state().exclusive()
+            && !link.state().exclusive()
+            && prev_link.state().access == link.state().access
+            && prev_link.state().layout == link.state().layout
+            && prev_link
+                .queues()
+                .all(|(prev, _)| link.queues().all(|(next, _)| prev != next));
+
+        if shared_read {
+            BoundaryCondition::SharedRead
+        } else if prev_link.single_queue() && !link.single_queue() {
+            // Prefer to generate barriers on the acquire side, if possible.
+            BoundaryCondition::SameFamilySingleToMultiQueue
+        } else {
+            BoundaryCondition::SameFamily
+        }
+    } else {
+        BoundaryCondition::FamilyTransfer
     }
 }
 
-fn sync_chain<R, S>(id: Id<R>, chain: &Chain<R>, schedule: &Schedule<S>, sync: &mut SyncTemp)
+fn sync_chain<R, S, Q>(
+    id: Id<R>,
+    chain: &Chain<R>,
+    schedule: &Schedule<S>,
+    queue_type: &Q,
+    sync: &mut SyncTemp,
+    explain: bool,
+) -> Vec<PendingDecision>
 where
     R: Resource,
+    Q: Fn(QueueFamilyId) -> QueueType,
     Id<R>: Into<Uid>,
     Guard<Semaphore, Semaphore>: Pick<R, Target = Barriers<R>>,
 {
+    if chain.external() {
+        // User synchronizes resources of external chains.
+        return Vec::new();
+    }
+
     let uid = id.into();
-    for (prev_link, link) in chain.links().windows(2).map(|pair| (&pair[0], &pair[1])) {
-        if prev_link.family() == link.family() {
-            // Prefer to generate barriers on the acquire side, if possible.
-            if prev_link.single_queue() && !link.single_queue() {
+
+    // Conservative chains and links with generic memory access
+    // use all stages supported by queues of the family.
+    let conservative = |link: &Link<R>, family| {
+        if chain.conservative() || link.state().access.is_generic() {
+            Some(all_commands() & supported_by(queue_type(family)))
+        } else {
+            None
+        }
+    };
+    // Generic memory access of the previous link may be performed by any command.
+    let source = |state: State<R>, family| {
+        if state.access.is_generic() {
+            State {
+                stages: all_commands() & supported_by(queue_type(family)),
+                ..state
+            }
+        } else {
+            state
+        }
+    };
+    let mut decisions = Vec::new();
+
+    // Barrier from discarded content does nothing if layout doesn't change (e.g. for buffers).
+    let first = chain
+        .links()
+        .first()
+        .filter(|link| chain.fresh() && link.state().layout != R::Layout::discard_content());
+    if let Some(link) = first {
+        let wait_sid = earliest(link, schedule);
+
+        // Generate barrier from discarded content in first link's first submission.
+        sync.get_sync(wait_sid).acquire.pick_mut().insert(
+            id,
+            Barrier::new(
+                State {
+                    access: R::Access::none(),
+                    layout: R::Layout::discard_content(),
+                    stages: PipelineStage::TOP_OF_PIPE,
+                }..State {
+                    stages: conservative(link, wait_sid.family()).unwrap_or(link.state().stages),
+                    ..link.state()
+                },
+            ),
+        );
+
+        if !link.single_queue() {
+            // Delay other queues in the link until the barrier finishes
+            for (queue_id, queue) in link.queues() {
+                if queue_id != wait_sid.queue() {
+                    let head = SubmissionId::new(queue_id, queue.first);
+                    generate_semaphore_pair(
+                        sync,
+                        uid,
+                        link,
+                        &conservative,
+                        wait_sid..head,
+                        Side::Acquire..Side::Acquire,
+                    );
+                }
+            }
+        }
+    }
+    for (index, (prev_link, link)) in chain
+        .links()
+        .windows(2)
+        .map(|pair| (&pair[0], &pair[1]))
+        .enumerate()
+    {
+        let mut semaphores = Vec::new();
+        let condition = boundary_condition(chain, prev_link, link);
+        // Barrier may be recorded on a queue of another family if resource is shared concurrently.
+        let next_state = |family| State {
+            stages: conservative(link, family).unwrap_or(link.state().stages),
+            ..link.state()
+        };
+        match condition {
+            BoundaryCondition::SharedRead => {
+                // Semaphores alone order reads of the next link after reads of the previous one.
+                // No memory dependency or layout transition is required.
+                for (prev_queue_id, prev_queue) in prev_link.queues() {
+                    let tail = SubmissionId::new(prev_queue_id, prev_queue.last);
+                    for (queue_id, queue) in link.queues() {
+                        let head = SubmissionId::new(queue_id, queue.first);
+                        semaphores.extend(generate_semaphore_pair(
+                            sync,
+                            uid,
+                            link,
+                            &conservative,
+                            tail..head,
+                            Side::Release..Side::Acquire,
+                        ));
+                    }
+                }
+            }
+            BoundaryCondition::SameFamilySingleToMultiQueue => {
                 let signal_sid = latest(prev_link, schedule);
 
                 // Generate barrier in prev link's last submission.
-                sync.get_sync(signal_sid)
-                    .release
-                    .pick_mut()
-                    .insert(id, Barrier::new(prev_link.state()..link.state()));
+                sync.get_sync(signal_sid).release.pick_mut().insert(
+                    id,
+                    Barrier::new(
+                        source(prev_link.state(), signal_sid.family())
+                            ..next_state(signal_sid.family()),
+                    ),
+                );
 
                 // Generate semaphores between queues in the previous link and the current one.
                 for (queue_id, queue) in link.queues() {
                     let head = SubmissionId::new(queue_id, queue.first);
-                    generate_semaphore_pair(
+                    semaphores.extend(generate_semaphore_pair(
                         sync,
                         uid,
                         link,
+                        &conservative,
                         signal_sid..head,
                         Side::Release..Side::Acquire,
-                    );
+                    ));
                 }
-            } else {
+            }
+            BoundaryCondition::SameFamily => {
                 let wait_sid = earliest(link, schedule);
 
                 // Generate semaphores between queues in the previous link and the current one.
                 for (queue_id, queue) in prev_link.queues() {
                     let tail = SubmissionId::new(queue_id, queue.last);
-                    generate_semaphore_pair(
+                    semaphores.extend(generate_semaphore_pair(
                         sync,
                         uid,
                         link,
+                        &conservative,
                         tail..wait_sid,
                         Side::Release..Side::Acquire,
-                    );
+                    ));
                 }
 
                 // Generate barrier in next link's first submission.
-                sync.get_sync(wait_sid)
-                    .acquire
-                    .pick_mut()
-                    .insert(id, Barrier::new(prev_link.state()..link.state()));
+                sync.get_sync(wait_sid).acquire.pick_mut().insert(
+                    id,
+                    Barrier::new(
+                        source(prev_link.state(), wait_sid.family())..next_state(wait_sid.family()),
+                    ),
+                );
 
                 if !link.single_queue() {
                     // Delay other queues in the link until the barrier finishes
                     for (queue_id, queue) in link.queues() {
                         if queue_id != wait_sid.queue() {
                             let head = SubmissionId::new(queue_id, queue.first);
-                            generate_semaphore_pair(
+                            semaphores.extend(generate_semaphore_pair(
                                 sync,
                                 uid,
                                 link,
+                                &conservative,
                                 wait_sid..head,
                                 Side::Acquire..Side::Acquire,
-                            );
+                            ));
                         }
                     }
                 }
             }
-        } else {
-            let signal_sid = latest(prev_link, schedule);
-            let wait_sid = earliest(link, schedule);
+            BoundaryCondition::FamilyTransfer => {
+                let signal_sid = latest(prev_link, schedule);
+                let wait_sid = earliest(link, schedule);
 
-            if !prev_link.single_queue() {
-                // Delay the last submission in the queue until other queues finish
-                for (queue_id, queue) in prev_link.queues() {
-                    if queue_id != signal_sid.queue() {
-                        let tail = SubmissionId::new(queue_id, queue.last);
-                        generate_semaphore_pair(
-                            sync,
-                            uid,
-                            prev_link,
-                            tail..signal_sid,
-                            Side::Release..Side::Release,
-                        );
+                if !prev_link.single_queue() {
+                    // Delay the last submission in the queue until other queues finish
+                    for (queue_id, queue) in prev_link.queues() {
+                        if queue_id != signal_sid.queue() {
+                            let tail = SubmissionId::new(queue_id, queue.last);
+                            semaphores.extend(generate_semaphore_pair(
+                                sync,
+                                uid,
+                                prev_link,
+                                &conservative,
+                                tail..signal_sid,
+                                Side::Release..Side::Release,
+                            ));
+                        }
                     }
                 }
-            }
 
-            // Generate a semaphore between the signal and wait sides of the transfer.
-            generate_semaphore_pair(
-                sync,
-                uid,
-                link,
-                signal_sid..wait_sid,
-                Side::Release..Side::Acquire,
-            );
-
-            // Generate barriers to transfer the resource to another queue.
-            sync.get_sync(signal_sid).release.pick_mut().insert(
-                id,
-                Barrier::release(
-                    signal_sid.queue()..wait_sid.queue(),
-                    State {
-                        access: prev_link.state().access,
-                        ..prev_link.queue_state(signal_sid.queue())
-                    }..,
-                    ..link.state().layout,
-                ),
-            );
-            sync.get_sync(wait_sid).acquire.pick_mut().insert(
-                id,
-                Barrier::acquire(
-                    signal_sid.queue()..wait_sid.queue(),
-                    prev_link.state().layout..,
-                    ..State {
-                        access: link.state().access,
-                        ..link.queue_state(wait_sid.queue())
-                    },
-                ),
-            );
-
-            if !link.single_queue() {
-                // Delay other queues in the link until the barrier finishes
-                for (queue_id, queue) in link.queues() {
-                    if queue_id != wait_sid.queue() {
-                        let head = SubmissionId::new(queue_id, queue.first);
-                        generate_semaphore_pair(
-                            sync,
-                            uid,
-                            link,
-                            wait_sid..head,
-                            Side::Acquire..Side::Acquire,
-                        );
+                // Generate a semaphore between the signal and wait sides of the transfer.
+                semaphores.extend(generate_semaphore_pair(
+                    sync,
+                    uid,
+                    link,
+                    &conservative,
+                    signal_sid..wait_sid,
+                    Side::Release..Side::Acquire,
+                ));
+
+                // Reads in the same layout leave nothing to make available or visible.
+                // Barriers of such transfer only change ownership of the resource.
+                let ownership_only = !prev_link.state().access.is_write()
+                    && !link.state().access.is_write()
+                    && prev_link.state().layout == link.state().layout;
+                let (release_access, acquire_access) = if ownership_only {
+                    (R::Access::none(), R::Access::none())
+                } else {
+                    (prev_link.state().access, link.state().access)
+                };
+
+                // Generate barriers to transfer the resource to another queue.
+                let transfer = sync.new_transfer();
+                sync.get_sync(signal_sid).release.pick_mut().insert(
+                    id,
+                    Barrier::release(
+                        transfer,
+                        signal_sid.queue()..wait_sid.queue(),
+                        source(
+                            State {
+                                access: release_access,
+                                ..prev_link.queue_state(signal_sid.queue())
+                            },
+                            signal_sid.family(),
+                        )..,
+                        ..link.state().layout,
+                    ),
+                );
+                sync.get_sync(wait_sid).acquire.pick_mut().insert(
+                    id,
+                    Barrier::acquire(
+                        transfer,
+                        signal_sid.queue()..wait_sid.queue(),
+                        prev_link.state().layout..,
+                        ..State {
+                            access: acquire_access,
+                            stages: conservative(link, wait_sid.family())
+                                .unwrap_or(link.queue(wait_sid.queue()).stages),
+                            ..link.queue_state(wait_sid.queue())
+                        },
+                    ),
+                );
+
+                if !link.single_queue() {
+                    // Delay other queues in the link until the barrier finishes
+                    for (queue_id, queue) in link.queues() {
+                        if queue_id != wait_sid.queue() {
+                            let head = SubmissionId::new(queue_id, queue.first);
+                            semaphores.extend(generate_semaphore_pair(
+                                sync,
+                                uid,
+                                link,
+                                &conservative,
+                                wait_sid..head,
+                                Side::Acquire..Side::Acquire,
+                            ));
+                        }
                     }
                 }
             }
         }
+
+        if explain {
+            decisions.push(PendingDecision {
+                links: index..index + 1,
+                condition,
+                semaphores,
+            });
+        }
     }
+    decisions
 }
 
 fn optimize_side(
@@ -674,3 +1916,131 @@ fn optimize<S>(schedule: &Schedule<S>, sync: &mut SyncTemp) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use collect::collect;
+    use pass::{Pass, StateUsage};
+    use std::collections::HashMap;
+    use std::iter::once;
+
+    fn pass(id: usize, family: usize, dependencies: &[usize], usage: StateUsage<Buffer>) -> Pass {
+        let mut buffers = HashMap::new();
+        buffers.insert(Id::new(0), usage);
+        Pass {
+            id: PassId(id),
+            family: QueueFamilyId(family),
+            queue: None,
+            dependencies: dependencies.iter().map(|&id| PassId(id)).collect(),
+            buffers,
+            images: HashMap::new(),
+        }
+    }
+
+    fn transfer_dst() -> StateUsage<Buffer> {
+        StateUsage {
+            state: State {
+                access: ::hal::buffer::Access::TRANSFER_WRITE,
+                layout: ::resource::BufferLayout,
+                stages: PipelineStage::TRANSFER,
+            },
+            usage: ::hal::buffer::Usage::TRANSFER_DST,
+        }
+    }
+
+    /// Buffer is written on family 0, read on family 1 and written on family 0 again.
+    fn ping_pong() -> SyncTemplate {
+        let passes = vec![
+            pass(0, 0, &[], transfer_dst()),
+            pass(1, 1, &[0], StateUsage::transfer_src()),
+            pass(2, 0, &[1], transfer_dst()),
+        ];
+        sync_template(&collect(passes, |_| 1))
+    }
+
+    fn queue(family: usize) -> QueueId {
+        QueueId::new(QueueFamilyId(family), 0)
+    }
+
+    #[test]
+    fn semaphore_balance() {
+        assert_eq!(ping_pong().validate_semaphore_balance(), Ok(()));
+    }
+
+    #[test]
+    fn semaphore_balance_missing_wait() {
+        let mut template = ping_pong();
+        let sid = SubmissionId::new(queue(0), 1);
+        let submission = template.schedule.submission_mut(sid).unwrap();
+        let slot = *submission.sync().acquire.wait[0].semaphore();
+        submission.sync_mut().acquire.wait.clear();
+        assert_eq!(
+            template.validate_semaphore_balance(),
+            Err(UnmatchedSemaphore {
+                slot,
+                signals: 1,
+                waits: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn semaphore_objects_reused_after_wait() {
+        // Second transfer is signaled by the submission that waits for the first one.
+        assert_eq!(ping_pong().semaphore_objects(), (1, vec![0, 0]));
+    }
+
+    #[test]
+    fn semaphore_objects_overlapping() {
+        // Two buffers are written by the same pass and read on different families.
+        // Both slots are signaled before either is waited.
+        let mut write = pass(0, 0, &[], transfer_dst());
+        write.buffers.insert(Id::new(1), transfer_dst());
+        let mut read = pass(2, 2, &[0], StateUsage::transfer_src());
+        read.buffers = once((Id::new(1), StateUsage::transfer_src())).collect();
+        let passes = vec![write, pass(1, 1, &[0], StateUsage::transfer_src()), read];
+        let template = sync_template(&collect(passes, |_| 1));
+        assert_eq!(template.validate_semaphore_balance(), Ok(()));
+        assert_eq!(template.semaphore_objects(), (2, vec![0, 1]));
+    }
+
+    #[test]
+    fn timeline_values() {
+        let timeline = ping_pong().timeline();
+        let first = timeline[SubmissionId::new(queue(0), 0)].sync();
+        assert_eq!(first.signal, 1);
+        assert!(first.waits.is_empty());
+
+        let read = timeline[SubmissionId::new(queue(1), 0)].sync();
+        assert_eq!(read.signal, 1);
+        assert_eq!(read.waits.len(), 1);
+        assert_eq!(read.waits[0].queue, queue(0));
+        assert_eq!(read.waits[0].value, 1);
+
+        let second = timeline[SubmissionId::new(queue(0), 1)].sync();
+        assert_eq!(second.signal, 2);
+        assert_eq!(second.waits.len(), 1);
+        assert_eq!(second.waits[0].queue, queue(1));
+        assert_eq!(second.waits[0].value, 1);
+    }
+
+    #[test]
+    fn bubbles() {
+        // Each queue idles for one submission while waiting for the other one.
+        assert_eq!(
+            ping_pong().estimate_bubbles(|_| 1),
+            vec![(queue(0), 1), (queue(1), 1)]
+        );
+    }
+
+    #[test]
+    fn no_bubbles_on_single_queue() {
+        let passes = vec![
+            pass(0, 0, &[], transfer_dst()),
+            pass(1, 0, &[0], StateUsage::transfer_src()),
+        ];
+        let template = sync_template(&collect(passes, |_| 1));
+        assert_eq!(template.estimate_bubbles(|_| 1), vec![(queue(0), 0)]);
+    }
+}